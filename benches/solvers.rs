@@ -0,0 +1,182 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use pathfinding::csr::CsrGraph;
+use pathfinding::exclusions::Exclusions;
+use pathfinding::generate::{Generator, Headway};
+use pathfinding::graph::{Graph, Timetable, Vertex};
+use pathfinding::input::Input;
+use pathfinding::journey::Journey;
+use pathfinding::json::Json;
+use pathfinding::report::Answer;
+use pathfinding::solvers::cost::{Fixed, Generalized};
+use pathfinding::solvers::stats::SearchStats;
+use pathfinding::solvers::{self, Solver};
+
+/*
+ * cargo bench --bench solvers
+ *
+ * Generates timetables at a few scales and times every phase on its own: reading the text with Graph::read,
+ * building the same graph straight from the generated lines, freezing it, and then queries between random
+ * stops for every solver on both graphs. Peak heap is what a phase allocates on top of what was live when
+ * it started. The numbers go to stdout and as JSON to target/bench/solvers.json, or wherever BENCH_OUTPUT
+ * points. BENCH_SCALES=small,medium picks the scales.
+ */
+
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            grow(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let moved = System.realloc(pointer, layout, new_size);
+        if !moved.is_null() {
+            LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+            grow(new_size);
+        }
+        moved
+    }
+}
+
+fn grow(size: usize) {
+    let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+// Runs a phase, returning what it made, how long it took and the most heap it needed
+fn measure<T>(phase: impl FnOnce() -> T) -> (T, Duration, usize) {
+    let live = LIVE.load(Ordering::Relaxed);
+    PEAK.store(live, Ordering::Relaxed);
+    let started = Instant::now();
+    let result = phase();
+    let elapsed = started.elapsed();
+    (result, elapsed, PEAK.load(Ordering::Relaxed) - live)
+}
+
+const SCALES: [(&str, u32, u32); 3] = [("small", 20, 100), ("medium", 100, 1000), ("large", 400, 5000)];
+const QUERIES: usize = 1000;
+const BUDGET: Duration = Duration::from_secs(2); // Per solver and graph, the slow ones stop early
+
+fn phase(elapsed: Duration, peak: usize) -> Json {
+    Json::object([("seconds", Json::Number(elapsed.as_secs_f64())), ("peak_heap_bytes", Json::Number(peak as f64))])
+}
+
+// Queries done, journeys found and the stats of all of them together
+fn queries<G: Timetable>(graph: &G, solve: Solver<G>, pairs: &[(Vertex, Vertex)]) -> (usize, usize, SearchStats) {
+    let started = Instant::now();
+    let (mut found, mut stats) = (0, SearchStats::default());
+    for (done, &(from, to)) in pairs.iter().enumerate() {
+        if started.elapsed() > BUDGET {
+            return (done, found, stats);
+        }
+        found += solve(graph, from, to, &Exclusions::none(), &mut stats).is_some() as usize;
+    }
+    (pairs.len(), found, stats)
+}
+
+// The generalized cost as one more solver, with weights that aren't the defaults
+fn cost<G: Timetable<Time = u32>>(graph: &G, from: Vertex, to: Vertex, exclusions: &Exclusions, stats: &mut SearchStats) -> Option<Journey> {
+    let weights = Generalized { wait: Fixed(50), ride: Fixed(125), transfer: Fixed(1000) };
+    let Answer::Cost { journey, .. } = Answer::cost(graph, from, to, 0, weights, exclusions, stats) else {
+        unreachable!()
+    };
+    journey
+}
+
+fn solver<G: Timetable<Time = u32>>(name: &str) -> Solver<G> {
+    match name {
+        "cost" => cost,
+        _ => solvers::named(name).unwrap().0,
+    }
+}
+
+fn main() {
+    let wanted = std::env::var("BENCH_SCALES").ok();
+    let output = std::env::var_os("BENCH_OUTPUT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/bench/solvers.json"));
+
+    let mut results = vec![];
+    for (scale, lines, stops) in SCALES {
+        if wanted.as_ref().is_some_and(|wanted| !wanted.split(',').any(|name| name == scale)) {
+            continue;
+        }
+        let generator = Generator { lines, stops, length: (5, 20), headway: Headway::Uniform(5, 30), seed: 42, ..Generator::default() };
+        let generated = generator.generate();
+        let text = generated.to_string();
+
+        let (graph, read_time, read_heap) = measure(|| Graph::read(&mut Input::new(text.as_bytes())).unwrap());
+        let (built, build_time, build_heap) = measure(|| generated.graph());
+        assert_eq!(built.to_string(), graph.to_string(), "reading the text and building from the lines disagree");
+        let (frozen, freeze_time, freeze_heap) = measure(|| graph.freeze());
+        let edges = graph.adjacency.iter().map(Vec::len).sum::<usize>();
+        println!(
+            "{}: {} bus lines, {} stops, {} legs; read {:.1?}, build {:.1?}, freeze {:.1?}",
+            scale, generated.lines.len(), stops, edges, read_time, build_time, freeze_time
+        );
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let pairs = (0..QUERIES).map(|_| (rng.random_range(0..stops), rng.random_range(0..stops))).collect::<Vec<_>>();
+        let mut solver_results = vec![];
+        for name in ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest", "cost"] {
+            for kind in ["adjacency", "csr"] {
+                let ((done, found, stats), elapsed, peak) = match kind {
+                    "adjacency" => measure(|| queries::<Graph>(&graph, solver(name), &pairs)),
+                    _ => measure(|| queries::<CsrGraph>(&frozen, solver(name), &pairs)),
+                };
+                let per_second = done as f64 / elapsed.as_secs_f64();
+                println!("  {:<13} {:<9} {:>10.1} queries/s  {:>12} bytes peak  ({} queries, {} found)", name, kind, per_second, peak, done, found);
+                solver_results.push(Json::object([
+                    ("solver", name.into()),
+                    ("graph", kind.into()),
+                    ("queries", (done as u32).into()),
+                    ("found", (found as u32).into()),
+                    ("seconds", Json::Number(elapsed.as_secs_f64())),
+                    ("queries_per_second", Json::Number(per_second)),
+                    ("peak_heap_bytes", Json::Number(peak as f64)),
+                    ("stats", stats.to_json()),
+                ]));
+            }
+        }
+
+        results.push(Json::object([
+            ("scale", scale.into()),
+            ("bus_lines", (generated.lines.len() as u32).into()),
+            ("stops", stops.into()),
+            ("legs", (edges as u32).into()),
+            ("read", phase(read_time, read_heap)),
+            ("build", phase(build_time, build_heap)),
+            ("freeze", phase(freeze_time, freeze_heap)),
+            ("solvers", Json::Array(solver_results)),
+        ]));
+    }
+
+    if let Some(directory) = output.parent() {
+        fs::create_dir_all(directory).unwrap();
+    }
+    fs::write(&output, Json::object([("seed", Json::Number(42.0)), ("scales", Json::Array(results))]).to_string()).unwrap();
+    println!("wrote {}", output.display());
+}
@@ -0,0 +1,38 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use pathfinding::exclusions::Exclusions;
+use pathfinding::format::Format;
+use pathfinding::graph::{Graph, Timetable, Vertex};
+use pathfinding::solvers::earliest;
+use pathfinding::solvers::stats::SearchStats;
+
+/*
+ * cargo +nightly fuzz run parse fuzz/corpus/parse
+ *
+ * Any text in any of the formats either loads or is an error, never a panic. Whatever loads can be
+ * frozen and searched: earliest arrival only, the other solvers can take forever on timetables that
+ * are valid but silly, like a bus every minute for eight thousand years.
+ */
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    for format in [Format::Text, Format::Json, Format::Csv] {
+        let Ok(graph) = Graph::parse(text, format) else {
+            continue;
+        };
+        let frozen = graph.freeze();
+        if graph.stop_count() == 0 {
+            continue; // Nowhere to go, the callers check for this
+        }
+        let home = graph.stop_count() as Vertex - 1;
+        for at in [0, u32::MAX] {
+            let journey = earliest::dijkstra_from(&graph, 0, home, at, &Exclusions::none(), &mut SearchStats::default());
+            let on_frozen = earliest::dijkstra_from(&frozen, 0, home, at, &Exclusions::none(), &mut SearchStats::default());
+            assert_eq!(journey.as_ref().map(|journey| journey.arrival()), on_frozen.map(|journey| journey.arrival()));
+            assert!(journey.is_none_or(|journey| journey.arrival() >= at), "arrived before setting off");
+        }
+    }
+});
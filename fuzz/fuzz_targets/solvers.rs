@@ -0,0 +1,144 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use pathfinding::audit::replay;
+use pathfinding::exclusions::Exclusions;
+use pathfinding::graph::{check_frequency, Graph, Timetable, Vertex, EVERY_DAY};
+use pathfinding::journey::Journey;
+use pathfinding::report::Answer;
+use pathfinding::solvers::cost::{Fixed, Generalized};
+use pathfinding::solvers::stats::SearchStats;
+use pathfinding::solvers::{self, earliest, profile};
+
+/*
+ * cargo +nightly fuzz run solvers fuzz/corpus/solvers
+ *
+ * Valid timetables made up from the fuzzer's bytes, run through every solver on the graph as built
+ * and frozen. Times go all the way up to u32::MAX, so sums near the end of time get tried. Kept
+ * small enough for the exhaustive max-wait search to finish.
+ */
+
+#[derive(Arbitrary, Debug)]
+struct Line {
+    stops: Vec<u8>,
+    first: u32,
+    rides: Vec<u8>, // Minutes between two stops, the times are added up so they never decrease
+}
+
+#[derive(Arbitrary, Debug)]
+struct Frequency {
+    line: Line,
+    headway: u16,
+    runs: u8,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Instance {
+    stops: u8,
+    lines: Vec<Line>,
+    frequencies: Vec<Frequency>,
+    from: u8,
+    to: u8,
+    at: u32,
+}
+
+const STOPS: u32 = 12;
+const LINES: usize = 24;
+const LENGTH: usize = 8;
+
+impl Line {
+    fn times(&self, stops: u32) -> (Vec<Vertex>, Vec<u32>) {
+        let locations = self.stops.iter().take(LENGTH).map(|&stop| stop as Vertex % stops).collect::<Vec<_>>();
+        let mut time = self.first;
+        let mut times = vec![time];
+        for i in 1..locations.len() {
+            time = time.saturating_add(self.rides.get(i).copied().unwrap_or(0) as u32);
+            times.push(time);
+        }
+        times.truncate(locations.len());
+        (locations, times)
+    }
+}
+
+impl Instance {
+    fn graph(&self) -> Graph {
+        let stops = self.stops as u32 % STOPS + 1;
+        let mut graph = Graph::new(stops as usize);
+        for line in self.lines.iter().take(LINES) {
+            let (locations, departures) = line.times(stops);
+            graph.add_trip(&locations, &departures, EVERY_DAY);
+        }
+        for frequency in self.frequencies.iter().take(4) {
+            // Offsets from the start of a run, which may start anywhere up to `first`
+            let (locations, mut offsets) = frequency.line.times(stops);
+            offsets.iter_mut().for_each(|offset| *offset -= frequency.line.first);
+            let headway = frequency.headway.max(1) as u32;
+            let first = frequency.line.first;
+            let last = first.saturating_add((frequency.runs % 16) as u32 * headway);
+            if check_frequency(&offsets, headway, (first, last)).is_ok() {
+                graph.add_frequency(&locations, &offsets, headway, (first, last), EVERY_DAY);
+            }
+        }
+        graph
+    }
+}
+
+// Legs one after the other from where and when the journey starts
+fn check<G: Timetable<Time = u32>>(graph: &G, journey: &Journey, from: Vertex, to: Vertex, at: u32) {
+    assert_eq!((journey.origin, journey.destination()), (from, to), "{}", journey);
+    assert!(journey.start >= at, "set off before {}: {}", at, journey);
+    if let Err(e) = replay(graph, journey, 0) {
+        panic!("{}: {}", e, journey);
+    }
+    assert!(journey.arrival() >= at, "arrived before setting off at {}: {}", at, journey);
+}
+
+// Not the defaults, and in hundredths so the rounding gets tried
+const WEIGHTS: Generalized = Generalized { wait: Fixed(50), ride: Fixed(125), transfer: Fixed(1000) };
+
+fn run<G: Timetable<Time = u32>>(graph: &G, instance: &Instance) -> Vec<Option<u64>> {
+    let stops = graph.stop_count() as u32;
+    let (from, to, at) = (instance.from as u32 % stops, instance.to as u32 % stops, instance.at);
+    let none = Exclusions::none();
+    let mut stats = SearchStats::default();
+    let mut answers = vec![];
+
+    for name in ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest"] {
+        let (solve, objective) = solvers::named::<G>(name).unwrap();
+        let journey = solve(graph, from, to, &none, &mut stats);
+        if let Some(journey) = &journey {
+            check(graph, journey, from, to, 0);
+        }
+        answers.push(journey.as_ref().map(objective).map(u64::from));
+    }
+
+    let journey = earliest::dijkstra_from(graph, from, to, at, &none, &mut stats);
+    if let Some(journey) = &journey {
+        check(graph, journey, from, to, at);
+    }
+    answers.push(journey.map(|journey| journey.arrival().into()));
+
+    let Answer::Cost { journey, breakdown, .. } = Answer::cost(graph, from, to, at, WEIGHTS, &none, &mut stats) else {
+        unreachable!()
+    };
+    if let Some(journey) = &journey {
+        check(graph, journey, from, to, at);
+    }
+    answers.push(breakdown.map(|breakdown| breakdown.cost().0));
+
+    for journey in profile::profile(graph, from, to, &none, &mut stats) {
+        check(graph, &journey, from, to, 0);
+    }
+    for (_, arrival) in profile::isochrone(graph, from, at, 30, &none, &mut stats) {
+        assert!(arrival >= at, "isochrone reached a stop at {} setting off at {}", arrival, at);
+    }
+    answers
+}
+
+fuzz_target!(|instance: Instance| {
+    let graph = instance.graph();
+    let answers = run(&graph, &instance);
+    assert_eq!(answers, run(&graph.freeze(), &instance), "the frozen graph answers differently");
+});
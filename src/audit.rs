@@ -0,0 +1,145 @@
+use crate::exclusions::Exclusions;
+use crate::graph::{Timetable, TripId, Vertex};
+use crate::journey::{Journey, Leg};
+use crate::json::Json;
+use crate::solvers;
+use crate::solvers::cost::Generalized;
+use crate::time::TimeLike;
+
+/*
+ * Replaying journeys against the timetable, to check answers from any solver, ours or someone else's.
+ * A journey holds up when every leg is a bus that actually runs and each one boards where the one before
+ * got off, no earlier than it got there. Changing buses takes `buffer` minutes on top, staying on takes none.
+ */
+pub fn replay<G: Timetable>(graph: &G, journey: &Journey<G::Time>, buffer: G::Time) -> Result<(), String> {
+    let none = Exclusions::none();
+    let (mut stop, mut time, mut trip): (_, _, Option<TripId>) = (journey.origin, journey.start, None);
+    for (i, leg) in journey.legs.iter().enumerate() {
+        if leg.from as usize >= graph.stop_count() {
+            return Err(format!("leg {} leaves {}, which isn't a stop", i + 1, leg.from));
+        }
+        if leg.from != stop {
+            return Err(format!("leg {} leaves {} but the journey is at {}", i + 1, leg.from, stop));
+        }
+        let ready = match trip {
+            Some(trip) if trip != leg.trip => time.saturating_add(buffer),
+            _ => time,
+        };
+        if leg.departure < ready {
+            return Err(format!("leg {} leaves {} at {}, before the journey can board at {}", i + 1, leg.from, leg.departure, ready));
+        }
+        // If a run of a frequency line leaves at the departure, it's the next one from there
+        if !graph.departures(leg.from, leg.departure, &none).any(|edge| Leg::new(leg.from, &edge) == *leg) {
+            return Err(format!(
+                "leg {} from {} to {} leaving {} arriving {} on trip {} isn't in the timetable",
+                i + 1, leg.from, leg.to, leg.departure, leg.arrival, leg.trip
+            ));
+        }
+        (stop, time, trip) = (leg.to, leg.arrival, Some(leg.trip));
+    }
+    Ok(())
+}
+
+/*
+ * Everything wrong with a route or cost answer as `pathfind --output json` writes it: the journey not holding
+ * up, going somewhere else than asked, its totals not adding up, or the answer not being what the solver
+ * makes of the journey. A cost answer, told apart by its "weights", has its breakdown worked out again from
+ * the journey and those weights. An error when the answer can't even be read.
+ */
+pub fn audit<G: Timetable<Time = u32>>(graph: &G, answer: &Json, buffer: u32) -> Result<Vec<String>, String> {
+    if let Some(weights) = answer.get("weights") {
+        return audit_cost(graph, answer, Generalized::from_json(weights)?, buffer);
+    }
+    let solver = answer.get("solver").and_then(Json::as_str).ok_or("the answer has no \"solver\"")?;
+    let (_, objective) = solvers::named::<G>(solver).ok_or_else(|| format!("unknown solver '{}'", solver))?;
+    let ends = ends(answer)?;
+    let value = match answer.get("answer") {
+        None | Some(Json::Null) => None,
+        Some(value) => Some(value.as_u32().ok_or("\"answer\" must be a whole number or null")?),
+    };
+
+    let mut problems = vec![];
+    let Some(stated) = answer.get("journey").filter(|journey| **journey != Json::Null) else {
+        if let Some(value) = value {
+            problems.push(format!("answers {} without a journey", value));
+        }
+        return Ok(problems);
+    };
+    if let Some(journey) = holds_up(graph, ends, stated, buffer, &mut problems)? {
+        if value != Some(objective(&journey)) {
+            problems.push(format!("{} answers {} but the journey gives {}", solver, Json::from(value), objective(&journey)));
+        }
+    }
+    Ok(problems)
+}
+
+fn audit_cost<G: Timetable<Time = u32>>(graph: &G, answer: &Json, weights: Generalized, buffer: u32) -> Result<Vec<String>, String> {
+    let ends = ends(answer)?;
+    let value = answer.get("answer").filter(|value| **value != Json::Null);
+    if value.is_some_and(|value| !matches!(value, Json::Number(_))) {
+        return Err("\"answer\" must be a number or null".to_string());
+    }
+
+    let mut problems = vec![];
+    let Some(stated) = answer.get("journey").filter(|journey| **journey != Json::Null) else {
+        if let Some(value) = value {
+            problems.push(format!("costs {} without a journey", value));
+        }
+        return Ok(problems);
+    };
+    if let Some(journey) = holds_up(graph, ends, stated, buffer, &mut problems)? {
+        let breakdown = weights.breakdown(&journey);
+        if let (Some(claimed), Json::Object(actual)) = (answer.get("breakdown"), breakdown.to_json()) {
+            for (key, actual) in actual {
+                match claimed.get(&key) {
+                    Some(claimed) if *claimed != actual => problems.push(format!("the breakdown says {} {} but it's {}", key, claimed, actual)),
+                    _ => {}
+                }
+            }
+        }
+        let cost = Json::from(breakdown.cost());
+        if value != Some(&cost) {
+            problems.push(format!("costs {} but the journey gives {}", value.unwrap_or(&Json::Null), cost));
+        }
+    }
+    Ok(problems)
+}
+
+// Where the answer was asked to go from and to
+fn ends(answer: &Json) -> Result<(Vertex, Vertex), String> {
+    let stop = |key: &str| answer.get(key).and_then(Json::as_u32).ok_or_else(|| format!("the answer has no \"{}\" stop", key));
+    Ok((stop("from")?, stop("to")?))
+}
+
+// The stated journey when it holds up, adding to `problems` where it goes wrong and which of its totals don't add up
+fn holds_up<G: Timetable<Time = u32>>(
+    graph: &G,
+    (from, to): (Vertex, Vertex),
+    stated: &Json,
+    buffer: u32,
+    problems: &mut Vec<String>,
+) -> Result<Option<Journey>, String> {
+    let journey = Journey::from_json(stated)?;
+    if (journey.origin, journey.destination()) != (from, to) {
+        problems.push(format!("asked from {} to {} but the journey goes from {} to {}", from, to, journey.origin, journey.destination()));
+    }
+    if let Err(e) = replay(graph, &journey, buffer) {
+        // Nothing adds up on a journey that can't be made
+        problems.push(e);
+        return Ok(None);
+    }
+
+    let totals = [
+        ("arrival", journey.arrival()),
+        ("wait", journey.wait_time()),
+        ("ride", journey.ride_time()),
+        ("transfers", journey.transfers() as u32),
+    ];
+    for (key, actual) in totals {
+        match stated.get(key) {
+            Some(claimed) if claimed.as_u32() != Some(actual) => problems.push(format!("the journey says {} {} but it's {}", key, claimed, actual)),
+            _ => {}
+        }
+    }
+    Ok(Some(journey))
+}
@@ -0,0 +1,243 @@
+use std::fs;
+use std::path::Path;
+
+use crate::calendar::{Calendar, Date, WeekdayMask};
+use crate::csr::CsrGraph;
+use crate::graph::{Edge, FrequencyEdge};
+
+/*
+ * Binary cache of a frozen graph, so a big timetable is parsed once and then loaded in one read.
+ * Everything is little endian:
+ *
+ *   magic     8 bytes, "PFGRAPH\0"
+ *   version   u32, FORMAT_VERSION
+ *   checksum  u64, FNV-1a of everything after it
+ *   counts    u32 each: stops, edges, frequency edges, trips, calendars
+ *   offsets   stops + 1 u32, then the edges as to, weight, departure time, trip
+ *   offsets   stops + 1 u32, then the frequency edges as to, weight, offset, headway, first, last, trip
+ *   trips     line, service, name length and the name in utf-8
+ *   calendars weekday mask, start, end, then the added and the removed dates, each preceded by their count
+ *
+ * Loading copies each section straight into a single vector, no per stop allocations or parsing. It stops short of
+ * zero-copy: an Edge in memory carries the cancelled flag and padding the file doesn't, and using the bytes in place
+ * would take unsafe casts that only hold for one layout and endianness. Stops are numbered in every format we read,
+ * so the stop registry is the stop count; the line metadata is the name, line and calendar of every trip.
+ */
+
+const MAGIC: &[u8; 8] = b"PFGRAPH\0";
+pub const FORMAT_VERSION: u32 = 1;
+const HEADER: usize = 8 + 4 + 8;
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32s(&mut self, values: &[u32]) {
+        values.iter().for_each(|&value| self.u32(value));
+    }
+
+    fn dates(&mut self, dates: &[Date]) {
+        self.u32(dates.len() as u32);
+        dates.iter().for_each(|date| self.i32(date.days()));
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|end| *end <= self.bytes.len());
+        let end = end.ok_or("graph cache is truncated")?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(self.u32()? as i32)
+    }
+
+    // `n` records of `width` u32s, checked against the bytes left before anything is allocated
+    fn records<T>(&mut self, n: u32, width: usize, record: impl Fn(&[u32]) -> T) -> Result<Vec<T>, String> {
+        let bytes = self.take((n as usize).checked_mul(width * 4).ok_or("graph cache is truncated")?)?;
+        let mut fields = vec![0; width];
+        Ok(bytes
+            .chunks_exact(width * 4)
+            .map(|chunk| {
+                for (field, value) in fields.iter_mut().zip(chunk.chunks_exact(4)) {
+                    *field = u32::from_le_bytes(value.try_into().unwrap());
+                }
+                record(&fields)
+            })
+            .collect())
+    }
+
+    fn dates(&mut self) -> Result<Vec<Date>, String> {
+        let n = self.u32()?;
+        self.records(n, 1, |date| Date::from_days(date[0] as i32))
+    }
+
+    // Offsets have to start at 0, never go down and end at the number of records they index
+    fn offsets(&mut self, stops: u32, records: u32) -> Result<Vec<u32>, String> {
+        let offsets = self.records(stops.checked_add(1).ok_or("graph cache has too many stops")?, 1, |offset| offset[0])?;
+        if offsets[0] != 0 || offsets.windows(2).any(|pair| pair[0] > pair[1]) || offsets[stops as usize] != records {
+            return Err("graph cache has corrupt offsets".to_string());
+        }
+        Ok(offsets)
+    }
+}
+
+impl CsrGraph {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut payload = Writer { bytes: vec![] };
+        let stops = self.offsets.len() as u32 - 1;
+        payload.u32s(&[
+            stops,
+            self.edges.len() as u32,
+            self.frequencies.len() as u32,
+            self.lines.len() as u32,
+            self.calendars.len() as u32,
+        ]);
+
+        payload.u32s(&self.offsets);
+        for edge in &self.edges {
+            payload.u32s(&[edge.to, edge.weight, edge.departure_time, edge.trip]);
+        }
+        payload.u32s(&self.frequency_offsets);
+        for edge in &self.frequencies {
+            payload.u32s(&[edge.to, edge.weight, edge.offset, edge.headway, edge.first, edge.last, edge.trip]);
+        }
+        for ((name, line), service) in self.names.iter().zip(&self.lines).zip(&self.services) {
+            payload.u32s(&[*line, *service, name.len() as u32]);
+            payload.bytes.extend_from_slice(name.as_bytes());
+        }
+        for calendar in &self.calendars {
+            payload.u32(calendar.weekdays.0 as u32);
+            payload.i32(calendar.start.days());
+            payload.i32(calendar.end.days());
+            payload.dates(&calendar.added);
+            payload.dates(&calendar.removed);
+        }
+
+        let mut bytes = Vec::with_capacity(HEADER + payload.bytes.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&fnv1a(&payload.bytes).to_le_bytes());
+        bytes.extend_from_slice(&payload.bytes);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<CsrGraph, String> {
+        if bytes.len() < HEADER || &bytes[..8] != MAGIC {
+            return Err("not a graph cache file".to_string());
+        }
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != FORMAT_VERSION {
+            return Err(format!(
+                "graph cache has format version {}, this build reads version {}; rebuild the cache from the timetable",
+                version, FORMAT_VERSION
+            ));
+        }
+        let checksum = u64::from_le_bytes(bytes[12..HEADER].try_into().unwrap());
+        if fnv1a(&bytes[HEADER..]) != checksum {
+            return Err("graph cache checksum mismatch, the file is damaged".to_string());
+        }
+
+        let mut reader = Reader { bytes: &bytes[HEADER..], pos: 0 };
+        let stops = reader.u32()?;
+        let edges = reader.u32()?;
+        let frequencies = reader.u32()?;
+        let trips = reader.u32()?;
+        let calendars = reader.u32()?;
+
+        let offsets = reader.offsets(stops, edges)?;
+        let edges = reader.records(edges, 4, |edge| Edge {
+            to: edge[0],
+            weight: edge[1],
+            departure_time: edge[2],
+            trip: edge[3],
+            cancelled: false,
+        })?;
+        let frequency_offsets = reader.offsets(stops, frequencies)?;
+        let frequencies = reader.records(frequencies, 7, |edge| FrequencyEdge {
+            to: edge[0],
+            weight: edge[1],
+            offset: edge[2],
+            headway: edge[3],
+            first: edge[4],
+            last: edge[5],
+            trip: edge[6],
+        })?;
+
+        // A trip takes at least 12 bytes, which keeps a bad count from reserving more than the file could hold
+        let capacity = (trips as usize).min((reader.bytes.len() - reader.pos) / 12);
+        let mut names = Vec::with_capacity(capacity);
+        let mut lines = Vec::with_capacity(capacity);
+        let mut services = Vec::with_capacity(capacity);
+        for _ in 0..trips {
+            lines.push(reader.u32()?);
+            services.push(reader.u32()?);
+            let length = reader.u32()? as usize;
+            let name = std::str::from_utf8(reader.take(length)?).map_err(|_| "graph cache has a trip name that isn't utf-8")?;
+            names.push(name.to_string());
+        }
+
+        let calendars = (0..calendars)
+            .map(|_| {
+                Ok(Calendar {
+                    weekdays: WeekdayMask(reader.u32()? as u8),
+                    start: Date::from_days(reader.i32()?),
+                    end: Date::from_days(reader.i32()?),
+                    added: reader.dates()?,
+                    removed: reader.dates()?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        // Everything that points somewhere else has to point inside the graph
+        let targets = edges.iter().map(|edge| (edge.to, edge.trip));
+        let frequent = frequencies.iter().map(|edge| (edge.to, edge.trip));
+        if targets.chain(frequent).any(|(to, trip)| to >= stops || trip >= trips)
+            || services.iter().any(|&service| service as usize >= calendars.len())
+        {
+            return Err("graph cache refers to stops, trips or calendars it doesn't have".to_string());
+        }
+        if frequencies.iter().any(|edge| edge.headway == 0 || edge.first > edge.last) {
+            return Err("graph cache has a frequency line without runs".to_string());
+        }
+        if reader.pos != reader.bytes.len() {
+            return Err("graph cache has trailing bytes".to_string());
+        }
+
+        Ok(CsrGraph { offsets, edges, frequency_offsets, frequencies, names, lines, services, calendars })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_bytes()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn load(path: &Path) -> Result<CsrGraph, String> {
+        let bytes = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        CsrGraph::from_bytes(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
@@ -0,0 +1,281 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+
+pub type ServiceId = u32;
+
+// Days since 1970-01-01, which was a Thursday.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Date(i32);
+
+impl Date {
+    pub const MIN: Date = Date(i32::MIN / 2);
+    pub const MAX: Date = Date(i32::MAX / 2);
+
+    pub fn from_days(days: i32) -> Date {
+        Date(days)
+    }
+
+    pub fn days(self) -> i32 {
+        self.0
+    }
+
+    // Civil date conversion, see http://howardhinnant.github.io/date_algorithms.html
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Date> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let yoe = y.rem_euclid(400);
+        let mp = (month + 9) % 12;
+        let doy = (153 * mp + 2) as i32 / 5 + day as i32 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        Some(Date(era * 146097 + doe - 719468))
+    }
+
+    pub fn ymd(self) -> (i32, u32, u32) {
+        let z = self.0 + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i32::from(month <= 2);
+        (year, month, day)
+    }
+
+    pub fn weekday(self) -> Weekday {
+        Weekday::ALL[(self.0 + 3).rem_euclid(7) as usize]
+    }
+
+    pub fn add_days(self, days: i32) -> Date {
+        Date(self.0 + days)
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+// Accepts both "2026-10-18" and the GTFS style "20261018".
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits: String = s.chars().filter(|c| *c != '-').collect();
+        if digits.len() != 8 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("invalid date '{}'", s));
+        }
+        let year = digits[0..4].parse().unwrap();
+        let month = digits[4..6].parse().unwrap();
+        let day = digits[6..8].parse().unwrap();
+        Date::from_ymd(year, month, day).ok_or_else(|| format!("invalid date '{}'", s))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+}
+
+// Bit i is set when the service runs on Weekday::ALL[i].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct WeekdayMask(pub u8);
+
+impl WeekdayMask {
+    pub const ALL: WeekdayMask = WeekdayMask(0b111_1111);
+    pub const WORKDAYS: WeekdayMask = WeekdayMask(0b001_1111);
+    pub const WEEKEND: WeekdayMask = WeekdayMask(0b110_0000);
+
+    pub fn contains(self, weekday: Weekday) -> bool {
+        self.0 & (1 << weekday as u8) != 0
+    }
+}
+
+// Written Monday first, like the GTFS calendar columns: "1111100" runs on workdays.
+impl FromStr for WeekdayMask {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 7 {
+            return Err(format!("weekday mask '{}' must have 7 digits", s));
+        }
+        s.chars().enumerate().try_fold(WeekdayMask(0), |mask, (i, c)| match c {
+            '1' => Ok(WeekdayMask(mask.0 | 1 << i)),
+            '0' => Ok(mask),
+            _ => Err(format!("invalid weekday mask '{}'", s)),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Calendar {
+    pub weekdays: WeekdayMask,
+    pub start: Date,
+    pub end: Date,
+    pub added: Vec<Date>,   // Extra service days, e.g. a holiday timetable
+    pub removed: Vec<Date>, // Days without service, wins over everything else
+}
+
+impl Calendar {
+    pub fn every_day() -> Calendar {
+        Calendar {
+            weekdays: WeekdayMask::ALL,
+            start: Date::MIN,
+            end: Date::MAX,
+            added: vec![],
+            removed: vec![],
+        }
+    }
+
+    pub fn runs_on(&self, date: Date) -> bool {
+        if self.removed.contains(&date) {
+            return false;
+        }
+        self.added.contains(&date)
+            || (self.start <= date && date <= self.end && self.weekdays.contains(date.weekday()))
+    }
+
+    // First day on or after `from` on which the service runs.
+    pub fn next_service_day(&self, from: Date) -> Option<Date> {
+        // Every weekday in the mask comes by within a week, and each removed date can push that back by at most one week
+        let horizon = 7 * (self.removed.len() as i32 + 1);
+        let first = from.max(self.start);
+        let regular = (0..horizon)
+            .map(|i| first.add_days(i))
+            .take_while(|date| *date <= self.end)
+            .find(|date| self.weekdays.contains(date.weekday()) && !self.removed.contains(date));
+
+        let extra = self
+            .added
+            .iter()
+            .filter(|date| **date >= from && !self.removed.contains(date))
+            .min()
+            .copied();
+
+        match (regular, extra) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+// "<mask> <start> <end> [+date] [-date] ...", e.g. "1111100 20260101 20261231 +20261226 -20261225"
+impl FromStr for Calendar {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.split_whitespace();
+        let mut field = |name: &str| split.next().ok_or_else(|| format!("calendar is missing its {}", name));
+        let weekdays = field("weekday mask")?.parse()?;
+        let start = field("start date")?.parse()?;
+        let end = field("end date")?.parse()?;
+
+        let mut calendar = Calendar { weekdays, start, end, added: vec![], removed: vec![] };
+        for exception in split {
+            if let Some(date) = exception.strip_prefix('+') {
+                calendar.added.push(date.parse()?);
+            } else if let Some(date) = exception.strip_prefix('-') {
+                calendar.removed.push(date.parse()?);
+            } else {
+                return Err(format!("calendar exception '{}' must start with + or -", exception));
+            }
+        }
+        Ok(calendar)
+    }
+}
+
+// A moment in a dated query. `time` is minutes after midnight and always below MINUTES_PER_DAY.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct DateTime {
+    pub date: Date,
+    pub time: u32,
+}
+
+impl DateTime {
+    pub fn new(date: Date, time: u32) -> DateTime {
+        DateTime::from_offset(date, time as i64)
+    }
+
+    // Normalises minutes relative to midnight of `date`, which may run past midnight or be negative.
+    pub fn from_offset(date: Date, minutes: i64) -> DateTime {
+        let day = MINUTES_PER_DAY as i64;
+        DateTime {
+            date: date.add_days(minutes.div_euclid(day) as i32),
+            time: minutes.rem_euclid(day) as u32,
+        }
+    }
+
+    // Minutes relative to midnight of `date`.
+    pub fn offset_from(self, date: Date) -> i64 {
+        (self.date.days() - date.days()) as i64 * MINUTES_PER_DAY as i64 + self.time as i64
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:02}:{:02}", self.date, self.time / 60, self.time % 60)
+    }
+}
+
+// Minutes after midnight, either as is or written as H:MM, hours past 23 are fine for late runs
+pub fn minutes(time: &str) -> Option<u32> {
+    match time.split_once(':') {
+        Some((hours, minutes)) if minutes.len() == 2 => {
+            let minutes = minutes.parse::<u32>().ok().filter(|&m| m < 60)?;
+            hours.parse::<u32>().ok()?.checked_mul(60)?.checked_add(minutes)
+        }
+        Some(_) => None,
+        None => time.parse().ok(),
+    }
+}
+
+// A stretch of time like "30m", "2h", "1h15m" or plain minutes
+pub fn duration(text: &str) -> Option<u32> {
+    if let Ok(minutes) = text.parse() {
+        return Some(minutes);
+    }
+    let (hours, rest) = match text.split_once('h') {
+        Some((hours, rest)) => (hours.parse::<u32>().ok()?, rest),
+        None => (0, text),
+    };
+    let minutes = match rest {
+        "" => 0,
+        rest => rest.strip_suffix('m')?.parse::<u32>().ok()?,
+    };
+    hours.checked_mul(60)?.checked_add(minutes)
+}
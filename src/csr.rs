@@ -0,0 +1,97 @@
+use crate::calendar::{Calendar, ServiceId};
+use crate::graph::{Edge, FrequencyEdge, Graph, LineId, Timetable, TripId, Vertex};
+use crate::time::TimeLike;
+
+/*
+ * A frozen copy of a Graph in compressed sparse row form: the legs of stop s are
+ * edges[offsets[s]..offsets[s + 1]], sorted by departure, so the first bus we can still
+ * catch is a binary search away and the ones that already left are never looked at.
+ * Real-time updates go to the Graph, freeze it again to query them here.
+ */
+pub struct CsrGraph<T = u32> {
+    pub(crate) offsets: Vec<u32>,
+    pub(crate) edges: Vec<Edge<T>>,
+    pub(crate) frequency_offsets: Vec<u32>,
+    pub(crate) frequencies: Vec<FrequencyEdge<T>>,
+    pub(crate) names: Vec<String>,       // Per trip
+    pub(crate) lines: Vec<LineId>,       // Per trip
+    pub(crate) services: Vec<ServiceId>, // Per trip, index into calendars
+    pub(crate) calendars: Vec<Calendar>,
+}
+
+impl<T: TimeLike> CsrGraph<T> {
+    pub fn edges(&self, stop: Vertex) -> &[Edge<T>] {
+        &self.edges[self.offsets[stop as usize] as usize..self.offsets[stop as usize + 1] as usize]
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn trip_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn trip_name(&self, trip: TripId) -> &str {
+        &self.names[trip as usize]
+    }
+}
+
+impl<T: TimeLike> Graph<T> {
+    pub fn freeze(&self) -> CsrGraph<T> {
+        let mut offsets = Vec::with_capacity(self.adjacency.len() + 1);
+        let mut edges = Vec::with_capacity(self.adjacency.iter().map(Vec::len).sum());
+        offsets.push(0);
+        for stop in &self.adjacency {
+            let first = edges.len();
+            edges.extend(stop.iter().filter(|edge| !edge.cancelled));
+            edges[first..].sort_by_key(|edge: &Edge<T>| edge.departure_time);
+            offsets.push(edges.len() as u32);
+        }
+
+        let mut frequency_offsets = Vec::with_capacity(self.frequencies.len() + 1);
+        let mut frequencies = vec![];
+        frequency_offsets.push(0);
+        for stop in &self.frequencies {
+            frequencies.extend(stop);
+            frequency_offsets.push(frequencies.len() as u32);
+        }
+
+        CsrGraph {
+            offsets,
+            edges,
+            frequency_offsets,
+            frequencies,
+            names: self.trips.iter().map(|trip| trip.name.clone()).collect(),
+            lines: self.trips.iter().map(|trip| trip.line).collect(),
+            services: self.trips.iter().map(|trip| trip.service).collect(),
+            calendars: self.calendars.clone(),
+        }
+    }
+}
+
+impl<T: TimeLike> Timetable for CsrGraph<T> {
+    type Time = T;
+
+    fn stop_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn line(&self, trip: TripId) -> LineId {
+        self.lines[trip as usize]
+    }
+
+    fn service(&self, trip: TripId) -> &Calendar {
+        &self.calendars[self.services[trip as usize] as usize]
+    }
+
+    fn legs_from(&self, stop: Vertex, time: T) -> impl Iterator<Item = &Edge<T>> {
+        let edges = self.edges(stop);
+        edges[edges.partition_point(|edge| edge.departure_time < time)..].iter()
+    }
+
+    fn frequencies_from(&self, stop: Vertex) -> &[FrequencyEdge<T>] {
+        let (from, to) = (self.frequency_offsets[stop as usize], self.frequency_offsets[stop as usize + 1]);
+        &self.frequencies[from as usize..to as usize]
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use crate::calendar::minutes;
+use crate::graph::{check_stops, Graph, Vertex, EVERY_DAY};
+
+// A stop visit, one row of the spreadsheet
+struct Visit {
+    row: usize,
+    seq: u32,
+    stop: Vertex,
+    time: u32,
+}
+
+// Splits a row on commas, double quoted fields may hold commas and "" for a quote
+fn fields(row: &str) -> Result<Vec<String>, String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quote".to_string());
+    }
+    fields.push(field);
+    Ok(fields.into_iter().map(|field| field.trim().to_string()).collect())
+}
+
+impl Graph {
+    /*
+     * Reads a spreadsheet export with one row per stop visit:
+     *
+     *   line_id,seq,stop,time
+     *   3,1,0,08:10
+     *   3,2,4,490
+     *
+     * Rows of the same line_id form one bus line, visited in order of seq, and lines are added in the
+     * order they first show up. That gives the same graph as the assignment format, except that trips are
+     * named after their line_id and keep their seq values as the stop_sequence of realtime feeds. The
+     * header is optional, times are minutes or H:MM, and every line runs every day. There are as many
+     * stops as the highest stop number plus one.
+     */
+    pub fn from_csv(text: &str) -> Result<Graph, String> {
+        let mut lines: Vec<(String, Vec<Visit>)> = vec![];
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut stops = 0;
+        let mut first = true; // Only the first row that isn't blank may be the header
+
+        for (i, row) in text.lines().enumerate() {
+            let row_number = i + 1;
+            let error = |message: String| format!("row {}: {}", row_number, message);
+            if row.trim().is_empty() {
+                continue;
+            }
+            let fields = fields(row).map_err(error)?;
+            if std::mem::replace(&mut first, false) && fields[0] == "line_id" {
+                continue;
+            }
+            let [line, seq, stop, time] = &fields[..] else {
+                return Err(error(format!("expected line_id,seq,stop,time but found {} fields", fields.len())));
+            };
+
+            let seq = seq.parse::<u32>().map_err(|_| error(format!("seq '{}' is not a number", seq)))?;
+            let stop = stop.parse::<Vertex>().map_err(|_| error(format!("stop '{}' is not a number", stop)))?;
+            let time = minutes(time).ok_or_else(|| error(format!("time '{}' is not minutes or H:MM", time)))?;
+            check_stops(stop as usize + 1).map_err(error)?;
+            stops = stops.max(stop as usize + 1);
+
+            let position = *index.entry(line.clone()).or_insert_with(|| {
+                lines.push((line.clone(), vec![]));
+                lines.len() - 1
+            });
+            lines[position].1.push(Visit { row: row_number, seq, stop, time });
+        }
+
+        let mut graph = Graph::new(stops);
+        for (name, mut visits) in lines {
+            visits.sort_by_key(|visit| visit.seq);
+            for pair in visits.windows(2) {
+                if pair[0].seq == pair[1].seq {
+                    return Err(format!("row {}: line {} visits seq {} twice", pair[1].row, name, pair[1].seq));
+                }
+                if pair[0].time > pair[1].time {
+                    return Err(format!(
+                        "row {}: line {} leaves stop {} at {}, before it got there at {}",
+                        pair[1].row, name, pair[1].stop, pair[1].time, pair[0].time
+                    ));
+                }
+            }
+
+            let locations = visits.iter().map(|visit| visit.stop).collect::<Vec<_>>();
+            let departures = visits.iter().map(|visit| visit.time).collect::<Vec<_>>();
+            let trip = graph.add_trip(&locations, &departures, EVERY_DAY);
+            let trip = &mut graph.trips[trip as usize];
+            trip.name = name;
+            trip.sequence = visits.iter().map(|visit| visit.seq).collect();
+        }
+        Ok(graph)
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::graph::{Graph, LineId, Vertex};
+use crate::journey::Journey;
+
+// Graphviz colours handed out to lines in turn
+const PALETTE: [&str; 8] = ["#1f77b4", "#ff7f0e", "#2ca02c", "#9467bd", "#8c564b", "#e377c2", "#17becf", "#bcbd22"];
+const HIGHLIGHT: &str = "#d62728";
+
+#[derive(Clone, Debug, Default)]
+pub struct DotOptions<'a> {
+    pub color_by_line: bool,
+    pub highlight: Option<&'a Journey>, // Drawn thick and red, with its stops filled in
+    pub collapse: bool,                 // One edge per pair of stops (and line, when coloured) with a count
+}
+
+// A bunch of legs going the same way, drawn as one edge when collapsing
+struct Bundle {
+    labels: Vec<(u32, String)>, // By first departure
+    highlighted: bool,
+}
+
+impl Graph {
+    /*
+     * Stops become nodes and legs become edges labelled "departure - arrival", frequency lines get
+     * a single edge saying how often they run. Pipe it through `dot -Tsvg` to look at it.
+     */
+    pub fn to_dot(&self, options: &DotOptions) -> String {
+        let on_journey = |from: Vertex, to: Vertex, departure: Option<u32>, trip: u32| {
+            options.highlight.is_some_and(|journey| {
+                journey.legs.iter().any(|leg| {
+                    leg.from == from && leg.to == to && leg.trip == trip && departure.is_none_or(|time| time == leg.departure)
+                })
+            })
+        };
+
+        // (from, to, line, leg) -> legs, the line is left out unless colouring and the leg number
+        // unless there's no collapsing. A BTreeMap keeps the output stable.
+        let mut bundles: BTreeMap<(Vertex, Vertex, Option<LineId>, usize), Bundle> = BTreeMap::new();
+        for (from, edges) in self.adjacency.iter().enumerate() {
+            let from = from as Vertex;
+            let explicit = edges.iter().filter(|edge| !edge.cancelled).map(|edge| {
+                let label = format!("{} - {}", edge.departure_time, edge.arrival());
+                let highlighted = on_journey(from, edge.to, Some(edge.departure_time), edge.trip);
+                (edge.to, edge.trip, (edge.departure_time, label), highlighted)
+            });
+            let frequent = self.frequencies[from as usize].iter().map(|edge| {
+                let first = edge.first.saturating_add(edge.offset);
+                let label = format!("every {} from {} to {}", edge.headway, first, edge.last_departure());
+                (edge.to, edge.trip, (first, label), on_journey(from, edge.to, None, edge.trip))
+            });
+
+            for (i, (to, trip, label, highlighted)) in explicit.chain(frequent).enumerate() {
+                let line = options.color_by_line.then(|| self.trips[trip as usize].line);
+                // Without collapsing every leg is its own bundle
+                let key = (from, to, line, if options.collapse { 0 } else { i });
+                let bundle = bundles.entry(key).or_insert(Bundle { labels: vec![], highlighted: false });
+                bundle.labels.push(label);
+                bundle.highlighted |= highlighted;
+            }
+        }
+
+        let mut dot = String::new();
+        writeln!(dot, "digraph timetable {{").unwrap();
+        writeln!(dot, "    rankdir=LR;").unwrap();
+        writeln!(dot, "    node [shape=circle];").unwrap();
+        for stop in 0..self.adjacency.len() as Vertex {
+            let visited = options
+                .highlight
+                .is_some_and(|journey| journey.origin == stop || journey.legs.iter().any(|leg| leg.to == stop));
+            if visited {
+                writeln!(dot, "    {} [style=filled, fillcolor=\"{}\", fontcolor=white];", stop, HIGHLIGHT).unwrap();
+            } else {
+                writeln!(dot, "    {};", stop).unwrap();
+            }
+        }
+
+        for ((from, to, line, _), mut bundle) in bundles {
+            bundle.labels.sort();
+            let label = match &bundle.labels[..] {
+                [(_, label)] => label.clone(),
+                labels => format!("{} departures, first {}", labels.len(), labels[0].1),
+            };
+            let mut attributes = vec![format!("label=\"{}\"", label)];
+            if bundle.highlighted {
+                attributes.push(format!("color=\"{}\", penwidth=3", HIGHLIGHT));
+            } else if let Some(line) = line {
+                attributes.push(format!("color=\"{}\"", PALETTE[line as usize % PALETTE.len()]));
+            }
+            writeln!(dot, "    {} -> {} [{}];", from, to, attributes.join(", ")).unwrap();
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
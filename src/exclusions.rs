@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use crate::graph::{Edge, LineId, Timetable, TripId, Vertex};
+use crate::journey::{Journey, Leg};
+use crate::solvers::stats::SearchStats;
+use crate::solvers::Solver;
+
+// Parts of the network a what-if query pretends are gone, honoured by Timetable::departures and so by every solver.
+#[derive(Clone, Debug, Default)]
+pub struct Exclusions {
+    pub stops: HashSet<Vertex>, // Closed stops, nothing arrives at or leaves from them
+    pub lines: HashSet<LineId>, // Lines that don't run at all
+    pub trips: HashSet<TripId>, // Single runs that don't happen
+}
+
+impl Exclusions {
+    pub fn none() -> Exclusions {
+        Exclusions::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stops.is_empty() && self.lines.is_empty() && self.trips.is_empty()
+    }
+
+    pub fn close_stop(mut self, stop: Vertex) -> Exclusions {
+        self.stops.insert(stop);
+        self
+    }
+
+    pub fn cancel_line(mut self, line: LineId) -> Exclusions {
+        self.lines.insert(line);
+        self
+    }
+
+    pub fn cancel_trip(mut self, trip: TripId) -> Exclusions {
+        self.trips.insert(trip);
+        self
+    }
+
+    pub fn allows<G: Timetable>(&self, graph: &G, from: Vertex, edge: &Edge<G::Time>) -> bool {
+        self.is_empty()
+            || !(self.stops.contains(&from)
+                || self.stops.contains(&edge.to)
+                || self.trips.contains(&edge.trip)
+                || self.lines.contains(&graph.line(edge.trip)))
+    }
+}
+
+// The same query answered on the whole network and with the exclusions in place
+#[derive(Clone, Debug)]
+pub struct WhatIf {
+    pub baseline: Option<Journey>,
+    pub restricted: Option<Journey>,
+}
+
+impl WhatIf {
+    pub fn run<G: Timetable<Time = u32>>(graph: &G, start: Vertex, end: Vertex, exclusions: &Exclusions, solve: Solver<G>) -> WhatIf {
+        WhatIf {
+            baseline: solve(graph, start, end, &Exclusions::none(), &mut SearchStats::default()),
+            restricted: solve(graph, start, end, exclusions, &mut SearchStats::default()),
+        }
+    }
+
+    // Legs of the baseline journey that are no longer taken
+    pub fn removed(&self) -> Vec<Leg> {
+        match (&self.baseline, &self.restricted) {
+            (Some(baseline), Some(restricted)) => baseline.legs_not_in(restricted),
+            (Some(baseline), None) => baseline.legs.clone(),
+            (None, _) => vec![],
+        }
+    }
+
+    // Legs taken instead
+    pub fn added(&self) -> Vec<Leg> {
+        match (&self.baseline, &self.restricted) {
+            (Some(baseline), Some(restricted)) => restricted.legs_not_in(baseline),
+            (None, Some(restricted)) => restricted.legs.clone(),
+            (_, None) => vec![],
+        }
+    }
+
+    // How much later we get home, None when either query finds no journey
+    pub fn arrival_delay(&self) -> Option<i64> {
+        let (baseline, restricted) = (self.baseline.as_ref()?, self.restricted.as_ref()?);
+        Some(restricted.arrival() as i64 - baseline.arrival() as i64)
+    }
+}
+
+impl Display for WhatIf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let summary = |journey: &Option<Journey>| match journey {
+            Some(journey) => format!("arrives {}, waits {}", journey.arrival(), journey.wait_time()),
+            None => "no journey".to_string(),
+        };
+        writeln!(f, "baseline:   {}", summary(&self.baseline))?;
+        write!(f, "restricted: {}", summary(&self.restricted))?;
+        if let Some(delay) = self.arrival_delay() {
+            write!(f, " ({:+})", delay)?;
+        }
+        for (sign, legs) in [('-', self.removed()), ('+', self.added())] {
+            for leg in legs {
+                write!(f, "\n{} {} -> {}  {} - {}  trip {}", sign, leg.from, leg.to, leg.departure, leg.arrival, leg.trip)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use std::fs;
+use std::io::{stdin, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::graph::Graph;
+use crate::input::Input;
+
+// The timetable formats we read
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+    Text, // The "b l" format of the assignments
+    Json,
+    Csv,
+}
+
+impl Format {
+    // Going by the extension, anything we don't know is taken to be the assignment format
+    pub fn from_path(path: &Path) -> Format {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Format::Json,
+            Some("csv") => Format::Csv,
+            _ => Format::Text,
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown input format '{}', expected text, json or csv", s)),
+        }
+    }
+}
+
+impl Graph {
+    pub fn parse(text: &str, format: Format) -> Result<Graph, String> {
+        match format {
+            Format::Text => Graph::read(&mut Input::new(text.as_bytes())),
+            Format::Json => Graph::from_json(text),
+            Format::Csv => Graph::from_csv(text),
+        }
+    }
+
+    // Reads stdin without a path, the format defaults to the one the extension suggests
+    pub fn load(path: Option<&Path>, format: Option<Format>) -> Result<Graph, String> {
+        let text = match path {
+            Some(path) => fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+            None => {
+                let mut text = String::new();
+                stdin().read_to_string(&mut text).map_err(|e| format!("stdin: {}", e))?;
+                text
+            }
+        };
+        let format = format.unwrap_or_else(|| path.map_or(Format::Text, Format::from_path));
+        let graph = Graph::parse(&text, format);
+        match path {
+            Some(path) => graph.map_err(|e| format!("{}: {}", path.display(), e)),
+            None => graph,
+        }
+    }
+}
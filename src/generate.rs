@@ -0,0 +1,143 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::{Rng, SeedableRng};
+
+use crate::calendar::minutes;
+use crate::graph::{Graph, Vertex, EVERY_DAY};
+
+// Minutes between two runs of the same line
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Headway {
+    Fixed(u32),
+    Uniform(u32, u32),
+    Exponential(f64), // Mean, for irregular service where buses bunch up
+}
+
+impl Headway {
+    fn sample(self, rng: &mut StdRng) -> u32 {
+        match self {
+            Headway::Fixed(minutes) => minutes,
+            Headway::Uniform(low, high) => rng.random_range(low..=high),
+            // Inverse transform sampling, never less than a minute so runs keep moving forward
+            Headway::Exponential(mean) => ((-mean * (1.0 - rng.random::<f64>()).ln()).round() as u32).max(1),
+        }
+    }
+}
+
+// "10", "5-20" or "exp:12"
+impl FromStr for Headway {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("headway '{}' should look like 10, 5-20 or exp:12", s);
+        let headway = if let Some(mean) = s.strip_prefix("exp:") {
+            Headway::Exponential(mean.parse::<f64>().ok().filter(|&mean| mean > 0.0 && mean.is_finite()).ok_or_else(error)?)
+        } else {
+            match range(s).ok_or_else(error)? {
+                (low, high) if low == high => Headway::Fixed(low),
+                (low, high) => Headway::Uniform(low, high),
+            }
+        };
+        match headway {
+            Headway::Fixed(0) | Headway::Uniform(0, _) => Err(error()),
+            headway => Ok(headway),
+        }
+    }
+}
+
+// "5" or "4-10", times may be H:MM
+pub fn range(s: &str) -> Option<(u32, u32)> {
+    let (low, high) = s.split_once('-').unwrap_or((s, s));
+    let (low, high) = (minutes(low)?, minutes(high)?);
+    (low <= high).then_some((low, high))
+}
+
+/*
+ * Random timetables in the assignment format. Every one of `lines` visits `length` different stops, spending
+ * `ride` minutes between two of them, and runs from a random moment in the first headway after the
+ * start of `span` until the end of it, each run becoming a bus line of its own. The same seed always gives the
+ * same timetable.
+ */
+#[derive(Clone, Debug)]
+pub struct Generator {
+    pub lines: u32,
+    pub stops: u32,
+    pub length: (u32, u32),
+    pub ride: (u32, u32),
+    pub headway: Headway,
+    pub span: (u32, u32),
+    pub seed: u64,
+}
+
+impl Default for Generator {
+    fn default() -> Generator {
+        Generator {
+            lines: 10,
+            stops: 50,
+            length: (3, 10),
+            ride: (1, 10),
+            headway: Headway::Fixed(15),
+            span: (6 * 60, 22 * 60),
+            seed: 0,
+        }
+    }
+}
+
+// The bus lines of a generated timetable, as the assignment format has them
+#[derive(Clone, Debug)]
+pub struct Generated {
+    pub stops: u32,
+    pub lines: Vec<(Vec<Vertex>, Vec<u32>)>,
+}
+
+impl Generator {
+    pub fn generate(&self) -> Generated {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut lines = vec![];
+        for _ in 0..self.lines {
+            let length = rng.random_range(self.length.0..=self.length.1).min(self.stops) as usize;
+            let locations = index::sample(&mut rng, self.stops as usize, length).into_iter().map(|stop| stop as Vertex).collect::<Vec<_>>();
+            let mut offsets = vec![0u32];
+            for _ in 1..length {
+                offsets.push(offsets.last().unwrap().saturating_add(rng.random_range(self.ride.0..=self.ride.1)));
+            }
+
+            // Runs stop where they would get past the end of time, rather than wrapping round to the start of it
+            let first = self.headway.sample(&mut rng);
+            let mut start = self.span.0.checked_add(rng.random_range(0..first));
+            while let Some(run) = start.filter(|&run| run <= self.span.1) {
+                let Some(departures) = offsets.iter().map(|&offset| run.checked_add(offset)).collect::<Option<Vec<_>>>() else {
+                    break;
+                };
+                lines.push((locations.clone(), departures));
+                start = run.checked_add(self.headway.sample(&mut rng));
+            }
+        }
+        Generated { stops: self.stops, lines }
+    }
+}
+
+impl Generated {
+    pub fn graph(&self) -> Graph {
+        let mut graph = Graph::new(self.stops as usize);
+        for (locations, departures) in &self.lines {
+            graph.add_trip(locations, departures, EVERY_DAY);
+        }
+        graph
+    }
+}
+
+impl Display for Generated {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join = |values: &[u32]| values.iter().map(u32::to_string).collect::<Vec<_>>().join(" ");
+        writeln!(f, "{} {}", self.lines.len(), self.stops)?;
+        for (locations, departures) in &self.lines {
+            writeln!(f, "{}", join(locations))?;
+            writeln!(f, "{}", join(departures))?;
+        }
+        Ok(())
+    }
+}
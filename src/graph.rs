@@ -0,0 +1,395 @@
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::iter::successors;
+
+use crate::calendar::{Calendar, Date, ServiceId, MINUTES_PER_DAY};
+use crate::exclusions::Exclusions;
+use crate::input::Input;
+use crate::time::TimeLike;
+
+pub type Vertex = u32;
+pub type TripId = u32;
+pub type LineId = u32;
+
+// Calendar every trip gets unless the input says otherwise
+pub const EVERY_DAY: ServiceId = 0;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Edge<T = u32> {
+    pub to: Vertex,
+    pub weight: T,
+    pub departure_time: T,
+    pub trip: TripId, // Bus line run this leg belongs to
+    pub cancelled: bool, // Set by a real-time update, kept so trip legs don't move around
+}
+
+impl<T: TimeLike> Edge<T> {
+    // Loaders never let a leg get past the end of time, so this only saturates on graphs built by hand
+    pub fn arrival(&self) -> T {
+        self.departure_time.saturating_add(self.weight)
+    }
+}
+
+// One leg of a frequency line. Runs start every `headway` minutes from `first` up to and including `last`,
+// and reach this leg `offset` minutes after they started.
+#[derive(Copy, Clone, Debug)]
+pub struct FrequencyEdge<T = u32> {
+    pub to: Vertex,
+    pub weight: T,
+    pub offset: T,
+    pub headway: T,
+    pub first: T,
+    pub last: T,
+    pub trip: TripId, // Shared by all runs of the line
+}
+
+impl<T: TimeLike> FrequencyEdge<T> {
+    // Departure of the first run leaving at or after `time`, without materialising the runs before it.
+    pub fn next_departure(&self, time: T) -> Option<T> {
+        let first = self.first.saturating_add(self.offset);
+        let runs = time.saturating_sub(first).div_ceil(self.headway);
+        let start = runs.checked_mul(self.headway)?.checked_add(self.first)?;
+        start.checked_add(self.offset).filter(|_| start <= self.last)
+    }
+
+    pub fn last_departure(&self) -> T {
+        let runs = self.last.saturating_sub(self.first).div_floor(self.headway);
+        self.first.saturating_add(runs.saturating_mul(self.headway)).saturating_add(self.offset)
+    }
+
+    pub fn run(&self, departure_time: T) -> Edge<T> {
+        Edge { to: self.to, weight: self.weight, departure_time, trip: self.trip, cancelled: false }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Trip<T = u32> {
+    pub name: String,        // Id used by external feeds, the trip number unless imported with one
+    pub line: LineId,        // Line this is a run of, its own trip number unless imported with one
+    pub service: ServiceId,  // Index into Graph.calendars
+    pub stops: Vec<Vertex>,  // Empty for frequency lines
+    pub schedule: Vec<T>,    // Planned departure at each stop, before any delays
+    pub sequence: Vec<u32>,  // Each stop's stop_sequence for external feeds, 0, 1, 2... unless imported with them
+    pub legs: Vec<usize>,    // Leg i is adjacency[stops[i]][legs[i]]
+}
+
+// Times in minutes unless told otherwise, Graph<u64> takes any timetable the assignment format can hold
+pub struct Graph<T = u32> {
+    pub adjacency: Vec<Vec<Edge<T>>>,
+    pub frequencies: Vec<Vec<FrequencyEdge<T>>>,
+    pub trips: Vec<Trip<T>>,
+    pub calendars: Vec<Calendar>,
+}
+
+impl<T: TimeLike> Graph<T> {
+    pub fn new(n: usize) -> Graph<T> {
+        Graph {
+            adjacency: vec![vec![]; n],
+            frequencies: vec![vec![]; n],
+            trips: vec![],
+            calendars: vec![Calendar::every_day()],
+        }
+    }
+
+    // Returns the position of the edge in the adjacency list of `from`
+    pub fn add_edge(&mut self, from: Vertex, to: Vertex, weight: T, departure_time: T, trip: TripId) -> usize {
+        let edges = &mut self.adjacency[from as usize];
+        edges.push(Edge { to, weight, departure_time, trip, cancelled: false });
+        edges.len() - 1
+    }
+
+    // Adds one run of a bus line, `departures[i]` being the time it leaves `locations[i]`.
+    pub fn add_trip(&mut self, locations: &[Vertex], departures: &[T], service: ServiceId) -> TripId {
+        let trip = self.trips.len() as TripId;
+        let legs = locations
+            .windows(2)
+            .zip(departures.windows(2))
+            .map(|(vertexes, weights)| {
+                self.add_edge(vertexes[0], vertexes[1], weights[1].saturating_sub(weights[0]), weights[0], trip)
+            })
+            .collect();
+
+        self.trips.push(Trip {
+            name: trip.to_string(),
+            line: trip,
+            service,
+            stops: locations.to_vec(),
+            schedule: departures.to_vec(),
+            sequence: (0..locations.len() as u32).collect(),
+            legs,
+        });
+        trip
+    }
+
+    // Adds a line running every `headway` minutes, `offsets[i]` being the minutes from the start of a run to `locations[i]`.
+    pub fn add_frequency(
+        &mut self,
+        locations: &[Vertex],
+        offsets: &[T],
+        headway: T,
+        (first, last): (T, T),
+        service: ServiceId,
+    ) -> TripId {
+        assert!(headway > T::ZERO, "headway must be positive");
+        let trip = self.trips.len() as TripId;
+        self.trips.push(Trip {
+            name: trip.to_string(),
+            line: trip,
+            service,
+            stops: vec![],
+            schedule: vec![],
+            sequence: vec![],
+            legs: vec![],
+        });
+
+        locations
+            .windows(2)
+            .zip(offsets.windows(2))
+            .for_each(|(vertexes, offsets)| {
+                self.frequencies[vertexes[0] as usize].push(FrequencyEdge {
+                    to: vertexes[1],
+                    weight: offsets[1].saturating_sub(offsets[0]),
+                    offset: offsets[0],
+                    headway,
+                    first,
+                    last,
+                    trip,
+                });
+            });
+        trip
+    }
+
+    /*
+     * Reads the assignment format: "b l", then per bus line its locations and departure times.
+     * The header may carry a third number f, the frequency lines following the bus lines:
+     *
+     *   0 3 7                              locations
+     *   0 4 9                              minutes from the start of a run to each location
+     *   10 360 1320                        headway, first and last start of a run
+     *
+     * It may be followed by an optional calendar section:
+     *
+     *   c                                  number of calendars
+     *   1111100 20260101 20261231 -20261225 c lines, see Calendar::from_str
+     *   0 1 1 ...                          calendar of each of the b bus lines and f frequency lines
+     *
+     * Without it every line runs every day.
+     */
+    pub fn read<B: BufRead>(input: &mut Input<B>) -> Result<Graph<T>, String> {
+        let mut header = input.expect_line("the header")?;
+        let (b, l) = (header.try_next::<u32>("bus line count")?, header.try_next::<u32>("stop count")?);
+        let f = header.try_optional::<u32>("frequency line count")?.unwrap_or(0);
+        check_stops(l as usize)?;
+        let mut graph = Graph::new(l as usize);
+
+        for i in 0..b {
+            let context = |message: String| format!("bus line {}: {}", i, message);
+            let locations = input.expect_line("the locations")?.try_collect::<Vertex>("location").map_err(context)?;
+            let departures = input.expect_line("the departures")?.try_collect::<T>("departure").map_err(context)?;
+            check_line(l, &locations, &departures).map_err(context)?;
+            graph.add_trip(&locations, &departures, EVERY_DAY);
+        }
+
+        for i in 0..f {
+            let context = |message: String| format!("frequency line {}: {}", i, message);
+            let locations = input.expect_line("the locations")?.try_collect::<Vertex>("location").map_err(context)?;
+            let offsets = input.expect_line("the offsets")?.try_collect::<T>("offset").map_err(context)?;
+            let mut line = input.expect_line("the headway")?;
+            let headway = line.try_next::<T>("headway").map_err(context)?;
+            let range = (line.try_next::<T>("first run").map_err(context)?, line.try_next::<T>("last run").map_err(context)?);
+            check_line(l, &locations, &offsets).map_err(context)?;
+            check_frequency(&offsets, headway, range).map_err(context)?;
+            graph.add_frequency(&locations, &offsets, headway, range, EVERY_DAY);
+        }
+
+        if let Some(mut line) = input.try_line()? {
+            let c = line.try_next::<usize>("calendar count")?;
+            let mut calendars = vec![];
+            for _ in 0..c {
+                calendars.push(input.expect_line("a calendar")?.split.collect::<Vec<_>>().join(" ").parse::<Calendar>()?);
+            }
+            graph.calendars = calendars;
+
+            let services = match input.try_line()? {
+                Some(line) => line.try_collect::<ServiceId>("calendar")?,
+                None => vec![],
+            };
+            if services.len() != graph.trips.len() {
+                return Err(format!("expected a calendar for each of the {} lines, got {}", graph.trips.len(), services.len()));
+            }
+            for (trip, service) in graph.trips.iter_mut().zip(services) {
+                if service as usize >= c {
+                    return Err(format!("unknown calendar {}", service));
+                }
+                trip.service = service;
+            }
+        }
+        Ok(graph)
+    }
+}
+
+// More stops than any network has, a bad header asking for billions would only run us out of memory
+pub const MAX_STOPS: usize = 1 << 20;
+
+pub fn check_stops(stops: usize) -> Result<(), String> {
+    if stops > MAX_STOPS {
+        return Err(format!("{} stops, more than the {} we can hold", stops, MAX_STOPS));
+    }
+    Ok(())
+}
+
+// What every loader checks of a line before adding it: known stops, and a time for each that never goes back
+pub fn check_line<T: TimeLike>(stops: u32, locations: &[Vertex], times: &[T]) -> Result<(), String> {
+    if let Some(stop) = locations.iter().find(|&&stop| stop >= stops) {
+        return Err(format!("unknown stop {}", stop));
+    }
+    if times.len() != locations.len() {
+        return Err(format!("{} stops but {} times", locations.len(), times.len()));
+    }
+    if times.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err("times must not decrease along the line".to_string());
+    }
+    Ok(())
+}
+
+// And of a frequency line, whose last run has to get to its last stop before time runs out
+pub fn check_frequency<T: TimeLike>(offsets: &[T], headway: T, (first, last): (T, T)) -> Result<(), String> {
+    if headway == T::ZERO || first > last {
+        return Err("frequency needs a headway and a first run before the last".to_string());
+    }
+    if offsets.last().is_some_and(|&offset| last.checked_add(offset).is_none()) {
+        return Err(format!("the run starting at {} would get past the end of time", last));
+    }
+    Ok(())
+}
+
+/*
+ * What the solvers need from a network, so they run on the Graph as built and on a frozen CsrGraph alike.
+ * Implementors only hand out their legs, how departures come out of them is the same for both.
+ */
+pub trait Timetable {
+    type Time: TimeLike;
+
+    fn stop_count(&self) -> usize;
+
+    fn line(&self, trip: TripId) -> LineId;
+
+    fn service(&self, trip: TripId) -> &Calendar;
+
+    // Explicit legs leaving `stop` at or after `time`, cancelled ones left out
+    fn legs_from(&self, stop: Vertex, time: Self::Time) -> impl Iterator<Item = &Edge<Self::Time>>;
+
+    fn frequencies_from(&self, stop: Vertex) -> &[FrequencyEdge<Self::Time>];
+
+    // Every explicit departure from `stop` at or after `time`, plus the next run of each frequency line.
+    // Later runs of a frequency line only arrive later, so they never help a search that wants to be early.
+    fn departures<'a>(&'a self, stop: Vertex, time: Self::Time, exclusions: &'a Exclusions) -> impl Iterator<Item = Edge<Self::Time>> + 'a
+    where
+        Self: Sized,
+    {
+        let explicit = self.legs_from(stop, time).copied();
+        let frequent = self
+            .frequencies_from(stop)
+            .iter()
+            .filter_map(move |edge| edge.next_departure(time).map(|departure| edge.run(departure)));
+        explicit.chain(frequent).filter(move |edge| exclusions.allows(self, stop, edge))
+    }
+
+    // Like `departures`, but with every later run of the frequency lines too, for searches that like to wait.
+    fn every_departure<'a>(&'a self, stop: Vertex, time: Self::Time, exclusions: &'a Exclusions) -> impl Iterator<Item = Edge<Self::Time>> + 'a
+    where
+        Self: Sized,
+    {
+        let explicit = self.legs_from(stop, time).copied();
+        let frequent = self.frequencies_from(stop).iter().flat_map(move |edge| {
+            let last = edge.last_departure();
+            let runs = successors(edge.next_departure(time), move |&run| run.checked_add(edge.headway).filter(|&next| next <= last));
+            runs.map(|departure| edge.run(departure))
+        });
+        explicit.chain(frequent).filter(move |edge| exclusions.allows(self, stop, edge))
+    }
+
+    // Like `departures` for a dated query: (minutes after midnight of `base`, leg) pairs, where the
+    // leg keeps the time of its own service day. Trips only count on days their calendar runs, which
+    // takes times in minutes.
+    fn dated_departures<'a>(
+        &'a self,
+        stop: Vertex,
+        base: Date,
+        time: i64,
+        exclusions: &'a Exclusions,
+    ) -> impl Iterator<Item = (i64, Edge)> + 'a
+    where
+        Self: Sized + Timetable<Time = u32>,
+    {
+        // Any leg may still have a run on a later day, so the time of day doesn't narrow them down here
+        let explicit = self.legs_from(stop, 0).filter_map(move |edge| {
+            let day = service_day(self.service(edge.trip), edge.departure_time, base, time)?;
+            Some((day + edge.departure_time as i64, *edge))
+        });
+        let frequent = self.frequencies_from(stop).iter().filter_map(move |edge| {
+            let day = service_day(self.service(edge.trip), edge.last_departure(), base, time)?;
+            let departure = edge.next_departure(u32::try_from((time - day).max(0)).ok()?)?;
+            Some((day + departure as i64, edge.run(departure)))
+        });
+        explicit.chain(frequent).filter(move |(_, edge)| exclusions.allows(self, stop, edge))
+    }
+}
+
+// Midnight (relative to `base`) of the first day `calendar` runs on whose departure at `latest` is not gone at `time`.
+// Departure times past MINUTES_PER_DAY belong to a run that started on the previous service day.
+fn service_day(calendar: &Calendar, latest: u32, base: Date, time: i64) -> Option<i64> {
+    let day = MINUTES_PER_DAY as i64;
+    let first = (time - latest as i64 + day - 1).div_euclid(day);
+    let service_day = calendar.next_service_day(base.add_days(first as i32))?;
+    Some((service_day.days() - base.days()) as i64 * day)
+}
+
+impl<T: TimeLike> Timetable for Graph<T> {
+    type Time = T;
+
+    fn stop_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    fn line(&self, trip: TripId) -> LineId {
+        self.trips[trip as usize].line
+    }
+
+    fn service(&self, trip: TripId) -> &Calendar {
+        &self.calendars[self.trips[trip as usize].service as usize]
+    }
+
+    fn legs_from(&self, stop: Vertex, time: T) -> impl Iterator<Item = &Edge<T>> {
+        self.adjacency[stop as usize]
+            .iter()
+            .filter(move |edge| !edge.cancelled && edge.departure_time >= time)
+    }
+
+    fn frequencies_from(&self, stop: Vertex) -> &[FrequencyEdge<T>] {
+        &self.frequencies[stop as usize]
+    }
+}
+
+impl<T: TimeLike> Display for Graph<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (vertex, edges) in self.adjacency.iter().enumerate() {
+            writeln!(f, "Vertex {}: ", vertex)?;
+            for edge in edges.iter().filter(|edge| !edge.cancelled) {
+                writeln!(
+                    f,
+                    "  -> to: {}, weight: {}, departure time: {}, trip: {}",
+                    edge.to, edge.weight, edge.departure_time, edge.trip
+                )?;
+            }
+            for edge in &self.frequencies[vertex] {
+                writeln!(
+                    f,
+                    "  -> to: {}, weight: {}, every {} from {} to {}, trip: {}",
+                    edge.to, edge.weight, edge.headway, edge.first.saturating_add(edge.offset), edge.last_departure(), edge.trip
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
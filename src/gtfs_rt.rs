@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::graph::{Graph, Trip, TripId, Vertex};
+use crate::realtime::{Status, Update};
+
+/*
+ * Just enough of the GTFS-Realtime protobuf to read TripUpdates, see
+ * https://gtfs.org/realtime/reference/ for the messages and field numbers used below.
+ * A stop_sequence is matched against the trip's own (see Trip.sequence), a stop_id is taken to be the
+ * location number. A trip visiting a stop twice has a stop_id update go to the first visit after the stop
+ * of the update before it, as updates come in stop order.
+ */
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TripUpdate {
+    pub trip_id: String,
+    pub cancelled: bool,
+    pub delay: Option<i32>, // Seconds, for the whole trip
+    pub stop_time_updates: Vec<StopTimeUpdate>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StopTimeUpdate {
+    pub stop_sequence: Option<u32>,
+    pub stop_id: Option<String>,
+    pub delay: Option<i32>, // Departure delay if given, the arrival delay otherwise
+    pub skipped: bool,
+}
+
+// GTFS-Realtime enum values we act on
+const TRIP_CANCELED: u64 = 3;
+const STOP_SKIPPED: u64 = 1;
+
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes, pos: 0 }
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.bytes.get(self.pos).ok_or("truncated varint")?;
+            self.pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("varint longer than 10 bytes".to_string())
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|end| *end <= self.bytes.len()).ok_or("truncated field")?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn field(&mut self) -> Result<Option<(u64, Value<'a>)>, String> {
+        if self.pos == self.bytes.len() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let value = match key & 7 {
+            0 => Value::Varint(self.varint()?),
+            1 => self.take(8).map(|_| Value::Fixed)?,
+            2 => {
+                let n = self.varint()? as usize;
+                Value::Bytes(self.take(n)?)
+            }
+            5 => self.take(4).map(|_| Value::Fixed)?,
+            wire => return Err(format!("unsupported wire type {}", wire)),
+        };
+        Ok(Some((key >> 3, value)))
+    }
+}
+
+fn string(bytes: &[u8]) -> Result<String, String> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| "string field is not utf-8".to_string())
+}
+
+// int32 fields are sign extended to 64 bits on the wire
+fn int32(value: u64) -> i32 {
+    value as i64 as i32
+}
+
+// FeedMessage: entity = 2, FeedEntity: trip_update = 3
+pub fn decode_feed(bytes: &[u8]) -> Result<Vec<TripUpdate>, String> {
+    let mut updates = vec![];
+    let mut feed = Reader::new(bytes);
+    while let Some((number, value)) = feed.field()? {
+        if let (2, Value::Bytes(entity)) = (number, value) {
+            let mut entity = Reader::new(entity);
+            while let Some((number, value)) = entity.field()? {
+                if let (3, Value::Bytes(trip_update)) = (number, value) {
+                    updates.push(decode_trip_update(trip_update)?);
+                }
+            }
+        }
+    }
+    Ok(updates)
+}
+
+// TripUpdate: trip = 1, stop_time_update = 2, delay = 5
+fn decode_trip_update(bytes: &[u8]) -> Result<TripUpdate, String> {
+    let mut update = TripUpdate::default();
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.field()? {
+        match (number, value) {
+            (1, Value::Bytes(descriptor)) => {
+                // TripDescriptor: trip_id = 1, schedule_relationship = 4
+                let mut descriptor = Reader::new(descriptor);
+                while let Some((number, value)) = descriptor.field()? {
+                    match (number, value) {
+                        (1, Value::Bytes(id)) => update.trip_id = string(id)?,
+                        (4, Value::Varint(relationship)) => update.cancelled = relationship == TRIP_CANCELED,
+                        _ => {}
+                    }
+                }
+            }
+            (2, Value::Bytes(stop_time_update)) => update.stop_time_updates.push(decode_stop_time_update(stop_time_update)?),
+            (5, Value::Varint(delay)) => update.delay = Some(int32(delay)),
+            _ => {}
+        }
+    }
+    Ok(update)
+}
+
+// StopTimeUpdate: stop_sequence = 1, arrival = 2, departure = 3, stop_id = 4, schedule_relationship = 5
+fn decode_stop_time_update(bytes: &[u8]) -> Result<StopTimeUpdate, String> {
+    let mut update = StopTimeUpdate::default();
+    let mut arrival = None;
+    let mut departure = None;
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.field()? {
+        match (number, value) {
+            (1, Value::Varint(sequence)) => update.stop_sequence = Some(sequence as u32),
+            (2, Value::Bytes(event)) => arrival = decode_delay(event)?,
+            (3, Value::Bytes(event)) => departure = decode_delay(event)?,
+            (4, Value::Bytes(id)) => update.stop_id = Some(string(id)?),
+            (5, Value::Varint(relationship)) => update.skipped = relationship == STOP_SKIPPED,
+            _ => {}
+        }
+    }
+    update.delay = departure.or(arrival);
+    Ok(update)
+}
+
+// StopTimeEvent: delay = 1. Absolute times need a service date, which the feed file doesn't give us.
+fn decode_delay(bytes: &[u8]) -> Result<Option<i32>, String> {
+    let mut delay = None;
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.field()? {
+        if let (1, Value::Varint(seconds)) = (number, value) {
+            delay = Some(int32(seconds));
+        }
+    }
+    Ok(delay)
+}
+
+// Reads a single feed file, or every file in a directory in file name order.
+pub fn read(path: &Path) -> Result<Vec<TripUpdate>, String> {
+    let error = |e: std::io::Error| format!("{}: {}", path.display(), e);
+    if !path.is_dir() {
+        let bytes = fs::read(path).map_err(error)?;
+        return decode_feed(&bytes).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+
+    let mut files = fs::read_dir(path)
+        .map_err(error)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(error)?;
+    files.sort();
+
+    let mut updates = vec![];
+    for file in files.iter().filter(|file| file.is_file()) {
+        updates.extend(read(file)?);
+    }
+    Ok(updates)
+}
+
+#[derive(Debug, Default)]
+pub struct Ingest {
+    pub applied: usize,       // Updates applied to the graph
+    pub rejected: Vec<String>, // Why the others weren't
+}
+
+impl Graph {
+    // Translates feed updates into our own and applies them, skipping what doesn't match the timetable.
+    pub fn apply_trip_updates(&mut self, updates: &[TripUpdate]) -> Ingest {
+        let trips: HashMap<String, TripId> = self
+            .trips
+            .iter()
+            .enumerate()
+            .map(|(id, trip)| (trip.name.clone(), id as TripId))
+            .collect();
+
+        let mut ingest = Ingest::default();
+        for update in updates {
+            let Some(&trip) = trips.get(&update.trip_id) else {
+                ingest.rejected.push(format!("unknown trip '{}'", update.trip_id));
+                continue;
+            };
+
+            let mut translated = vec![];
+            if update.cancelled {
+                translated.push(Update { trip, stop_index: 0, status: Status::Cancelled });
+            } else if let Some(delay) = update.delay {
+                // Holds until the first stop update, if there is one
+                translated.push(Update { trip, stop_index: 0, status: Status::Delayed(delay) });
+            }
+            let Trip { stops, sequence, .. } = &self.trips[trip as usize];
+            let mut previous: Option<usize> = None;
+            for stop_time in update.stop_time_updates.iter().filter(|_| !update.cancelled) {
+                let stop_index = match (stop_time.stop_sequence, &stop_time.stop_id) {
+                    (Some(wanted), _) => sequence.iter().position(|&number| number == wanted).ok_or(format!("no stop_sequence {}", wanted)),
+                    (None, Some(id)) => {
+                        let after = previous.map_or(0, |previous| previous + 1);
+                        let stop = id.parse::<Vertex>().ok();
+                        stops[after.min(stops.len())..]
+                            .iter()
+                            .position(|&s| Some(s) == stop)
+                            .map(|i| after + i)
+                            .ok_or(format!("no stop_id '{}' from stop index {} on", id, after))
+                    }
+                    (None, None) => Err("stop update without stop".to_string()),
+                };
+                if let Ok(stop_index) = stop_index {
+                    previous = Some(stop_index);
+                }
+                match (stop_index, stop_time.delay) {
+                    (_, _) if stop_time.skipped => {
+                        ingest.rejected.push(format!("trip '{}': skipped stops are not supported", update.trip_id));
+                    }
+                    (Ok(stop_index), Some(delay)) => {
+                        translated.push(Update { trip, stop_index: stop_index as u32, status: Status::Delayed(delay) })
+                    }
+                    (Ok(_), None) => ingest.rejected.push(format!("trip '{}': stop update without delay", update.trip_id)),
+                    (Err(e), _) => ingest.rejected.push(format!("trip '{}': {}", update.trip_id, e)),
+                }
+            }
+
+            // Each delay holds until the next one, so they go in stop order, the trip's delay first
+            translated.sort_by_key(|update| update.stop_index);
+            for translated in translated {
+                match self.apply(&translated) {
+                    Ok(()) => ingest.applied += 1,
+                    Err(e) => ingest.rejected.push(e),
+                }
+            }
+        }
+        ingest
+    }
+}
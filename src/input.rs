@@ -0,0 +1,72 @@
+use std::io::BufRead;
+use std::str::{FromStr, SplitWhitespace};
+
+/*
+ * Input parsing with help of lovely rust community https://users.rust-lang.org/t/reading-stdin-line-by-line-selectively/32536/2
+ */
+pub struct Input<B> {
+    inner: B,
+    buffer: String,
+}
+
+impl<B: BufRead> Input<B> {
+    pub fn new(inner: B) -> Input<B> {
+        Self {
+            inner,
+            buffer: String::new(),
+        }
+    }
+
+    // The next line, an error once the input is exhausted
+    pub fn expect_line(&mut self, what: &str) -> Result<Line<'_>, String> {
+        self.buffer.clear();
+        match self.inner.read_line(&mut self.buffer) {
+            Ok(0) => Err(format!("input ended before {}", what)),
+            Ok(_) => Ok(Line {
+                split: self.buffer.split_whitespace(),
+            }),
+            Err(e) => Err(format!("reading {}: {}", what, e)),
+        }
+    }
+
+    // The next line that isn't blank, `None` once the input is exhausted.
+    // Used for the optional sections that may follow the bus lines.
+    pub fn try_line(&mut self) -> Result<Option<Line<'_>>, String> {
+        loop {
+            self.buffer.clear();
+            if self.inner.read_line(&mut self.buffer).map_err(|e| format!("reading a line: {}", e))? == 0 {
+                return Ok(None);
+            }
+            if !self.buffer.trim().is_empty() {
+                break;
+            }
+        }
+        Ok(Some(Line {
+            split: self.buffer.split_whitespace(),
+        }))
+    }
+}
+
+pub struct Line<'a> {
+    pub split: SplitWhitespace<'a>,
+}
+
+// Every value is checked, `what` is the value in the error
+impl Line<'_> {
+    pub fn try_next<T: FromStr>(&mut self, what: &str) -> Result<T, String> {
+        let value = self.split.next().ok_or_else(|| format!("missing {}", what))?;
+        value.parse::<T>().map_err(|_| format!("bad {} '{}'", what, value))
+    }
+
+    // The next value if the line has one left, for trailing fields that may be left out
+    pub fn try_optional<T: FromStr>(&mut self, what: &str) -> Result<Option<T>, String> {
+        self.split
+            .next()
+            .map(|value| value.parse::<T>().map_err(|_| format!("bad {} '{}'", what, value)))
+            .transpose()
+    }
+
+    pub fn try_collect<T: FromStr>(self, what: &str) -> Result<Vec<T>, String> {
+        self.split.map(|value| value.parse::<T>().map_err(|_| format!("bad {} '{}'", what, value))).collect()
+    }
+}
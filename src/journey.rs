@@ -0,0 +1,79 @@
+use std::fmt::{Display, Formatter};
+
+use crate::graph::{Edge, TripId, Vertex};
+use crate::time::TimeLike;
+
+// One ride on a bus, from boarding until getting off at the next stop
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Leg<T = u32> {
+    pub from: Vertex,
+    pub to: Vertex,
+    pub departure: T,
+    pub arrival: T,
+    pub trip: TripId,
+}
+
+impl<T: TimeLike> Leg<T> {
+    pub fn new(from: Vertex, edge: &Edge<T>) -> Leg<T> {
+        Leg {
+            from,
+            to: edge.to,
+            departure: edge.departure_time,
+            arrival: edge.arrival(),
+            trip: edge.trip,
+        }
+    }
+}
+
+// What a solver answers with: where and when we set off, and every leg taken from there
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Journey<T = u32> {
+    pub origin: Vertex,
+    pub start: T,
+    pub legs: Vec<Leg<T>>,
+}
+
+// The totals saturate rather than wrap on a journey that can't be made, see audit::replay for telling those apart
+impl<T: TimeLike> Journey<T> {
+    pub fn destination(&self) -> Vertex {
+        self.legs.last().map_or(self.origin, |leg| leg.to)
+    }
+
+    pub fn arrival(&self) -> T {
+        self.legs.last().map_or(self.start, |leg| leg.arrival)
+    }
+
+    pub fn ride_time(&self) -> T {
+        self.legs.iter().fold(T::ZERO, |ride, leg| ride.saturating_add(leg.arrival.saturating_sub(leg.departure)))
+    }
+
+    // Everything that isn't riding, the wait before the first bus included
+    pub fn wait_time(&self) -> T {
+        self.arrival().saturating_sub(self.start).saturating_sub(self.ride_time())
+    }
+
+    // Staying on the same trip from one leg to the next isn't a transfer
+    pub fn transfers(&self) -> usize {
+        self.legs.windows(2).filter(|legs| legs[0].trip != legs[1].trip).count()
+    }
+
+    // The transfers as the value a solver optimises, which comes in the type of the times
+    pub fn transfer_count(&self) -> T {
+        T::from(u16::try_from(self.transfers()).unwrap_or(u16::MAX))
+    }
+
+    // Legs of this journey that `other` doesn't take
+    pub fn legs_not_in(&self, other: &Journey<T>) -> Vec<Leg<T>> {
+        self.legs.iter().filter(|leg| !other.legs.contains(leg)).copied().collect()
+    }
+}
+
+impl<T: TimeLike> Display for Journey<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "depart {} at {}", self.origin, self.start)?;
+        for leg in &self.legs {
+            writeln!(f, "  {} -> {}  {} - {}  trip {}", leg.from, leg.to, leg.departure, leg.arrival, leg.trip)?;
+        }
+        write!(f, "arrive {} at {}, waited {}", self.destination(), self.arrival(), self.wait_time())
+    }
+}
@@ -0,0 +1,492 @@
+use std::fmt::{Display, Formatter};
+
+use crate::calendar::Calendar;
+use crate::graph::{check_frequency, check_line, check_stops, Graph, Vertex, EVERY_DAY};
+use crate::journey::{Journey, Leg};
+use crate::solvers::cost::{Breakdown, Fixed, Generalized, NO_WALKING};
+
+// Just enough JSON for our timetables and answers, numbers are kept as f64 like JavaScript does
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>), // In insertion order, so output reads the way it was built
+}
+
+impl Json {
+    pub fn parse(text: &str) -> Result<Json, String> {
+        let mut parser = Parser { bytes: text.as_bytes(), pos: 0, depth: 0 };
+        let value = parser.value()?;
+        parser.whitespace();
+        if parser.pos != parser.bytes.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+
+    pub fn object<K: Into<String>>(fields: impl IntoIterator<Item = (K, Json)>) -> Json {
+        Json::Object(fields.into_iter().map(|(key, value)| (key.into(), value)).collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_u32(&self) -> Option<u32> {
+        match *self {
+            Json::Number(n) if n >= 0.0 && n <= u32::MAX as f64 && n.fract() == 0.0 => Some(n as u32),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl From<u32> for Json {
+    fn from(n: u32) -> Json {
+        Json::Number(n as f64)
+    }
+}
+
+// In minutes, decimals and all
+impl From<Fixed> for Json {
+    fn from(n: Fixed) -> Json {
+        Json::Number(n.0 as f64 / 100.0)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut Formatter<'_>, s: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+// Arrays and objects in arrays and objects, any deeper and the recursion would run out of stack
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("invalid JSON at byte {}: {}", self.pos, message)
+    }
+
+    fn whitespace(&mut self) {
+        while self.bytes.get(self.pos).is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), String> {
+        if self.bytes[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", literal)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("nested too deep"));
+        }
+        self.depth += 1;
+        let value = self.element();
+        self.depth -= 1;
+        value
+    }
+
+    fn element(&mut self) -> Result<Json, String> {
+        self.whitespace();
+        match self.bytes.get(self.pos) {
+            None => Err(self.error("unexpected end of input")),
+            Some(b'n') => self.expect("null").map(|_| Json::Null),
+            Some(b't') => self.expect("true").map(|_| Json::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut values = vec![];
+                self.whitespace();
+                if self.bytes.get(self.pos) == Some(&b']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    self.whitespace();
+                    match self.bytes.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Json::Array(values));
+                        }
+                        _ => return Err(self.error("expected ',' or ']'")),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = vec![];
+                self.whitespace();
+                if self.bytes.get(self.pos) == Some(&b'}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.whitespace();
+                    if self.bytes.get(self.pos) != Some(&b'"') {
+                        return Err(self.error("expected a key"));
+                    }
+                    let key = self.string()?;
+                    self.whitespace();
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                    self.whitespace();
+                    match self.bytes.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Json::Object(fields));
+                        }
+                        _ => return Err(self.error("expected ',' or '}'")),
+                    }
+                }
+            }
+            Some(_) => self.number(),
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.bytes.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    // -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?, which f64::from_str is more lenient than
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        let skip = |parser: &mut Self, bytes: &[u8]| {
+            let found = parser.bytes.get(parser.pos).is_some_and(|b| bytes.contains(b));
+            parser.pos += usize::from(found);
+            found
+        };
+        skip(self, b"-");
+        let leading_zero = self.bytes.get(self.pos) == Some(&b'0');
+        let mut valid = match self.digits() {
+            0 => false,
+            n => !leading_zero || n == 1,
+        };
+        if valid && skip(self, b".") {
+            valid = self.digits() > 0;
+        }
+        if valid && skip(self, b"eE") {
+            skip(self, b"+-");
+            valid = self.digits() > 0;
+        }
+        if !valid {
+            self.pos = start;
+            return Err(self.error("expected a value"));
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+        text.parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(Json::Number)
+            .ok_or_else(|| self.error("number out of range"))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            let start = self.pos;
+            while self.bytes.get(self.pos).is_some_and(|&b| b != b'"' && b != b'\\' && b >= 0x20) {
+                self.pos += 1;
+            }
+            s.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).map_err(|_| self.error("invalid utf-8"))?);
+            match self.bytes.get(self.pos) {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some(b'\\') => {
+                    let escape = *self.bytes.get(self.pos + 1).ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 2;
+                    match escape {
+                        b'"' => s.push('"'),
+                        b'\\' => s.push('\\'),
+                        b'/' => s.push('/'),
+                        b'b' => s.push('\u{8}'),
+                        b'f' => s.push('\u{c}'),
+                        b'n' => s.push('\n'),
+                        b'r' => s.push('\r'),
+                        b't' => s.push('\t'),
+                        b'u' => s.push(self.unicode_escape()?),
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                _ => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let hex = self.bytes.get(self.pos..self.pos + 4).ok_or_else(|| self.error("short unicode escape"))?;
+        let hex = std::str::from_utf8(hex).map_err(|_| self.error("invalid unicode escape"))?;
+        let code = u32::from_str_radix(hex, 16).map_err(|_| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    // \uXXXX, with UTF-16 surrogate pairs for everything outside the basic plane
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            self.expect("\\u")?;
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("invalid surrogate pair"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+}
+
+/*
+ * The JSON timetable, with the same meaning as the whitespace format:
+ *
+ *   {
+ *     "stops": 4,                                  or an array with an entry per stop
+ *     "lines": [
+ *       { "stops": [0, 1, 3], "departures": [[10, 20, 30], [40, 50, 60]], "service": 1 },
+ *       { "stops": [1, 2], "frequency": { "offsets": [0, 4], "headway": 10, "first": 360, "last": 1320 } }
+ *     ],
+ *     "calendars": ["1111111 20260101 20261231", "1111100 20260101 20261231 -20261225"]
+ *   }
+ *
+ * Every entry of "departures" is a run of the line, all runs of a line share its line number. A line needs
+ * at least one run, from "departures", a "frequency" or both.
+ * "service" and "calendars" are optional, see Calendar::from_str for the calendar strings.
+ */
+impl Graph {
+    pub fn from_json(text: &str) -> Result<Graph, String> {
+        let json = Json::parse(text)?;
+        let stops = match json.get("stops") {
+            Some(Json::Array(stops)) => stops.len(),
+            Some(stops) => stops.as_u32().ok_or("\"stops\" must be a count or an array")? as usize,
+            None => return Err("timetable has no \"stops\"".to_string()),
+        };
+        check_stops(stops)?;
+        let mut graph = Graph::new(stops);
+
+        if let Some(calendars) = json.get("calendars") {
+            let calendars = calendars.as_array().ok_or("\"calendars\" must be an array")?;
+            graph.calendars = calendars
+                .iter()
+                .map(|calendar| calendar.as_str().ok_or("a calendar must be a string")?.parse::<Calendar>())
+                .collect::<Result<_, _>>()?;
+        }
+
+        let lines = json.get("lines").and_then(Json::as_array).ok_or("timetable has no \"lines\" array")?;
+        for (number, line) in lines.iter().enumerate() {
+            let context = |message: &str| format!("line {}: {}", number, message);
+            let numbers = |key: &str, value: Option<&Json>| -> Result<Vec<u32>, String> {
+                let values = value.and_then(Json::as_array).ok_or_else(|| context(&format!("\"{}\" must be an array", key)))?;
+                values
+                    .iter()
+                    .map(|value| value.as_u32().ok_or_else(|| context(&format!("\"{}\" must hold whole numbers", key))))
+                    .collect()
+            };
+
+            let locations: Vec<Vertex> = numbers("stops", line.get("stops"))?;
+            let service = match line.get("service") {
+                Some(service) => service.as_u32().ok_or_else(|| context("\"service\" must be a number"))?,
+                None => EVERY_DAY,
+            };
+            if service as usize >= graph.calendars.len() {
+                return Err(context(&format!("unknown calendar {}", service)));
+            }
+
+            let first_trip = graph.trips.len() as u32;
+            let runs = match line.get("departures") {
+                Some(runs) => runs.as_array().ok_or_else(|| context("\"departures\" must be an array"))?,
+                None => &[],
+            };
+            for run in runs {
+                let departures = numbers("departures", Some(run))?;
+                check_line(stops as u32, &locations, &departures).map_err(|e| context(&e))?;
+                graph.add_trip(&locations, &departures, service);
+            }
+            if let Some(frequency) = line.get("frequency") {
+                let offsets = numbers("offsets", frequency.get("offsets"))?;
+                let field = |key: &str| frequency.get(key).and_then(Json::as_u32).ok_or_else(|| context(&format!("frequency needs \"{}\"", key)));
+                let (headway, first, last) = (field("headway")?, field("first")?, field("last")?);
+                check_line(stops as u32, &locations, &offsets).map_err(|e| context(&e))?;
+                check_frequency(&offsets, headway, (first, last)).map_err(|e| context(&e))?;
+                graph.add_frequency(&locations, &offsets, headway, (first, last), service);
+            }
+            if graph.trips.len() as u32 == first_trip {
+                return Err(context("no runs, a line needs \"departures\" or a \"frequency\""));
+            }
+
+            // All runs of the line share the line number of its first trip
+            for trip in &mut graph.trips[first_trip as usize..] {
+                trip.line = first_trip;
+            }
+        }
+        Ok(graph)
+    }
+}
+
+impl Leg {
+    pub fn from_json(json: &Json) -> Result<Leg, String> {
+        let field = |key: &str| json.get(key).and_then(Json::as_u32).ok_or_else(|| format!("a leg needs \"{}\"", key));
+        Ok(Leg { from: field("from")?, to: field("to")?, departure: field("departure")?, arrival: field("arrival")?, trip: field("trip")? })
+    }
+
+    pub fn to_json(&self) -> Json {
+        Json::object([
+            ("from", self.from.into()),
+            ("to", self.to.into()),
+            ("departure", self.departure.into()),
+            ("arrival", self.arrival.into()),
+            ("trip", self.trip.into()),
+        ])
+    }
+}
+
+impl Journey {
+    // Only what makes the journey, the totals to_json adds are left for whoever wants to check them
+    pub fn from_json(json: &Json) -> Result<Journey, String> {
+        let field = |key: &str| json.get(key).and_then(Json::as_u32).ok_or_else(|| format!("a journey needs \"{}\"", key));
+        let legs = json.get("legs").and_then(Json::as_array).ok_or("a journey needs \"legs\"")?;
+        Ok(Journey { origin: field("origin")?, start: field("start")?, legs: legs.iter().map(Leg::from_json).collect::<Result<_, _>>()? })
+    }
+
+    pub fn to_json(&self) -> Json {
+        Json::object([
+            ("origin", self.origin.into()),
+            ("destination", self.destination().into()),
+            ("start", self.start.into()),
+            ("arrival", self.arrival().into()),
+            ("wait", self.wait_time().into()),
+            ("ride", self.ride_time().into()),
+            ("transfers", (self.transfers() as u32).into()),
+            ("legs", Json::Array(self.legs.iter().map(Leg::to_json).collect())),
+        ])
+    }
+}
+
+impl Generalized {
+    // The weights as to_json writes them, each a number with at most two decimals
+    pub fn from_json(json: &Json) -> Result<Generalized, String> {
+        if json.get("walk").is_some() {
+            return Err(NO_WALKING.to_string());
+        }
+        let weight = |key: &str| match json.get(key) {
+            Some(Json::Number(n)) => n.to_string().parse::<Fixed>().map_err(|e| format!("weight \"{}\": {}", key, e)),
+            _ => Err(format!("the weights need \"{}\"", key)),
+        };
+        Ok(Generalized { wait: weight("wait")?, ride: weight("ride")?, transfer: weight("transfer")? })
+    }
+
+    pub fn to_json(&self) -> Json {
+        Json::object([("wait", self.wait.into()), ("ride", self.ride.into()), ("transfer", self.transfer.into())])
+    }
+}
+
+impl Breakdown {
+    pub fn to_json(&self) -> Json {
+        Json::object([
+            ("wait", self.wait.into()),
+            ("ride", self.ride.into()),
+            ("transfers", (self.transfers as u32).into()),
+            ("wait_cost", self.wait_cost.into()),
+            ("ride_cost", self.ride_cost.into()),
+            ("transfer_cost", self.transfer_cost.into()),
+            ("cost", self.cost().into()),
+        ])
+    }
+}
@@ -0,0 +1,21 @@
+pub mod cache;
+pub mod audit;
+pub mod calendar;
+pub mod csr;
+pub mod csv;
+pub mod dot;
+pub mod exclusions;
+pub mod format;
+pub mod generate;
+pub mod graph;
+pub mod gtfs_rt;
+pub mod input;
+pub mod journey;
+pub mod json;
+pub mod logger;
+pub mod realtime;
+pub mod repl;
+pub mod report;
+pub mod server;
+pub mod solvers;
+pub mod time;
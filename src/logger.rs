@@ -0,0 +1,52 @@
+use std::fmt::Write;
+
+use log::kv::{Error, Key, Value, VisitSource};
+use log::{LevelFilter, Log, Metadata, Record};
+
+/*
+ * Writes log events to stderr as "LEVEL target: message key=value ...", so stdout keeps carrying
+ * only answers. The solvers log every heap push and pop, relaxation, pruned label and cache hit at
+ * trace level and a line per search at debug level.
+ */
+struct Stderr;
+
+static STDERR: Stderr = Stderr;
+
+// Appends the key=value pairs of an event
+struct Fields<'a>(&'a mut String);
+
+impl<'kvs> VisitSource<'kvs> for Fields<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+        write!(self.0, " {}={}", key, value).unwrap();
+        Ok(())
+    }
+}
+
+impl Log for Stderr {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", line(record));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+// An event as it goes to stderr
+pub fn line(record: &Record) -> String {
+    let mut line = format!("{:<5} {}: {}", record.level(), record.target(), record.args());
+    record.key_values().visit(&mut Fields(&mut line)).unwrap();
+    line
+}
+
+// Can only be done once per process, the level being off, error, warn, info, debug or trace
+pub fn init(level: &str) -> Result<(), String> {
+    let level = level.parse::<LevelFilter>().map_err(|_| format!("unknown log level '{}'", level))?;
+    log::set_logger(&STDERR).map_err(|e| e.to_string())?;
+    log::set_max_level(level);
+    Ok(())
+}
@@ -1,222 +1,391 @@
-use std::collections::{HashMap, VecDeque};
-use std::fmt::{Debug, Display, Formatter};
-use std::io::{stdin, BufRead, BufReader};
-use std::str::{FromStr, SplitWhitespace};
-
-struct Input<B> {
-    inner: B,
-    buffer: String,
+use std::fs;
+use std::io::{stdin, stdout};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::sync::Arc;
+
+use pathfinding::audit::audit;
+use pathfinding::calendar::{minutes, Date, DateTime};
+use pathfinding::csr::CsrGraph;
+use pathfinding::dot::DotOptions;
+use pathfinding::exclusions::Exclusions;
+use pathfinding::format::Format;
+use pathfinding::generate::{range, Generator};
+use pathfinding::graph::{Graph, Timetable, Vertex};
+use pathfinding::json::Json;
+use pathfinding::logger;
+use pathfinding::repl::Session;
+use pathfinding::report::{Answer, Report};
+use pathfinding::server::serve;
+use pathfinding::solvers::cost::{Generalized, NO_WALKING};
+use pathfinding::solvers::stats::SearchStats;
+use pathfinding::solvers::{self, earliest, profile};
+
+/*
+ * One binary for every question, reading the timetable once per run:
+ *
+ *   pathfind earliest|min-wait|min-transfers|max-wait|dag-longest
+ *                                                     best journey by that solver, from --from to --to
+ *   pathfind cost [--wait 2] [--ride 1] [--transfer 5] the journey from --from to --to that feels shortest: minutes
+ *                                                     waited and ridden times their weight, plus minutes for every
+ *                                                     change of bus; weights may have two decimals like 1.5;
+ *                                                     there are no footpaths, so --walk is an error
+ *   pathfind profile                                  every departure worth taking from --from to --to
+ *   pathfind matrix                                   earliest arrivals between stops, all of them by default
+ *   pathfind isochrone --within 30                    stops reachable from --from within 30 minutes
+ *   pathfind dot [--color] [--collapse] [--highlight SOLVER]
+ *                                                     the timetable in Graphviz DOT: legs coloured by line, legs
+ *                                                     between the same stops drawn as one, the journey by SOLVER
+ *                                                     from --from to --to drawn thick
+ *   pathfind repl --input FILE                        load once, then answer queries typed on stdin
+ *   pathfind serve --listen 127.0.0.1:8080            load once, then answer over HTTP, see server.rs
+ *   pathfind generate --lines 10 --stops 50 ...       a random timetable in the assignment format, see below
+ *   pathfind check --answer FILE [--buffer 2]         replay a route or cost answer saved with --output json
+ *                                                     against the timetable, with minutes to spare when changing
+ *                                                     buses
+ *
+ * Common flags:
+ *
+ *   --input FILE             the timetable, stdin without
+ *   --cache FILE             answer from the frozen graph saved in FILE, building it from --input and saving it
+ *                            there first when FILE is missing or older than --input; not for repl and serve
+ *   --format text|json|csv   by default whatever the extension says, the assignment format otherwise
+ *   --updates FILE           delays and cancellations to apply to the timetable first, one a line as
+ *                            "TRIP STOP SECONDS" or "TRIP STOP cancelled"; not with --cache
+ *   --from STOP              where to set off, Mercator (0) by default; a comma separated list for matrix
+ *   --to STOP                where to go, home (the last stop) by default; a comma separated list for matrix
+ *   --at TIME                when to set off, minutes or H:MM; for earliest, cost, matrix and isochrone
+ *   --date DATE              for earliest: set off at --at on DATE (2026-10-18 or 20261018) and only take trips
+ *                            whose calendar runs on their service day, answering with the date and time we arrive
+ *   --closed STOPS           comma separated stops to pretend are closed, for every query but repl and serve
+ *   --cancel-line LINES      lines to pretend don't run, likewise
+ *   --cancel-trip TRIPS      single runs to pretend don't run, likewise
+ *   --output text|json       just the answer, or the answer with its journeys and stats
+ *   --stats                  also print what the searches went through to stderr
+ *   --log debug|trace        search events on stderr, a line per search or every step of it
+ */
+
+const COMMANDS: &str = "earliest|min-wait|min-transfers|max-wait|dag-longest|cost|dot|profile|matrix|isochrone|repl|serve|generate|check";
+
+fn usage(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!(
+        "usage: pathfind {} [--input FILE] [--format text|json|csv] [--cache FILE] [--updates FILE] [--from STOP] [--to STOP] [--at TIME] [--date DATE] [--closed STOPS] [--cancel-line LINES] [--cancel-trip TRIPS] [--within MINUTES] [--wait W] [--ride W] [--transfer MINUTES] [--color] [--collapse] [--highlight SOLVER] [--listen ADDRESS] [--answer FILE] [--buffer MINUTES] [--output text|json] [--stats] [--log LEVEL]",
+        COMMANDS
+    );
+    exit(2)
 }
 
-impl<B: BufRead> Input<B> {
-    pub fn new(inner: B) -> Input<B> {
-        Self {
-            inner,
-            buffer: String::new(),
-        }
+// Stops, lines or trips, all numbered
+fn ids(list: &str, what: &str) -> Vec<u32> {
+    list.split(',').map(|id| id.trim().parse().unwrap_or_else(|_| usage(&format!("'{}' is not a {}", id, what)))).collect()
+}
+
+// Exactly one stop, or the default when none was given
+fn single(list: &Option<Vec<Vertex>>, default: Vertex, flag: &str) -> Vertex {
+    match list.as_deref() {
+        None => default,
+        Some([stop]) => *stop,
+        Some(_) => usage(&format!("{} takes a single stop for this command", flag)),
     }
+}
 
-    pub fn line(&mut self) -> Line {
-        self.buffer.clear();
-        self.inner.read_line(&mut self.buffer).unwrap();
-        Line {
-            split: self.buffer.split_whitespace(),
+/*
+ * pathfind generate takes its own flags, printing the timetable to stdout:
+ *
+ *   --lines N                lines, each running from the start to the end of the span (10)
+ *   --stops N                stops in the timetable (50)
+ *   --length N or MIN-MAX    stops a line visits (3-10)
+ *   --ride N or MIN-MAX      minutes between two stops of a line (1-10)
+ *   --headway 10|5-20|exp:12 minutes between two runs of a line, fixed, uniform or exponential (15)
+ *   --span FIRST-LAST        when the lines run, minutes or H:MM (6:00-22:00)
+ *   --seed N                 the same seed gives the same timetable (0)
+ */
+fn generate(mut args: impl Iterator<Item = String>) {
+    let mut generator = Generator::default();
+    let bad = |flag: &str, value: &str| -> ! { usage(&format!("bad value '{}' for {}", value, flag)) };
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_else(|| usage(&format!("{} needs a value", flag)));
+        match flag.as_str() {
+            "--lines" => generator.lines = value.parse().unwrap_or_else(|_| bad(&flag, &value)),
+            "--stops" => generator.stops = value.parse().ok().filter(|&stops| stops > 0).unwrap_or_else(|| bad(&flag, &value)),
+            "--length" => generator.length = range(&value).filter(|&(low, _)| low > 0).unwrap_or_else(|| bad(&flag, &value)),
+            "--ride" => generator.ride = range(&value).unwrap_or_else(|| bad(&flag, &value)),
+            "--headway" => generator.headway = value.parse().unwrap_or_else(|e: String| usage(&e)),
+            "--span" => generator.span = range(&value).unwrap_or_else(|| bad(&flag, &value)),
+            "--seed" => generator.seed = value.parse().unwrap_or_else(|_| bad(&flag, &value)),
+            _ => usage(&format!("unknown flag {}", flag)),
         }
     }
+    print!("{}", generator.generate());
 }
 
-struct Line<'a> {
-    split: SplitWhitespace<'a>,
+fn load(path: Option<&Path>, format: Option<Format>) -> Graph {
+    let graph = Graph::load(path, format).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(1)
+    });
+    if graph.stop_count() == 0 {
+        eprintln!("the timetable has no stops");
+        exit(1)
+    }
+    graph
 }
 
-impl<'a> Line<'a> {
-    fn next<T: FromStr>(&mut self) -> T
-    where
-        T: FromStr,
-        T::Err: Debug,
-    {
-        self.split.next().unwrap().parse::<T>().unwrap()
+// Whether the cache is there and no older than the timetable it was built from
+fn fresh(cache: &Path, path: Option<&Path>) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    match (modified(cache), path.map(modified)) {
+        (None, _) => false,
+        (Some(cache), Some(Some(input))) => cache >= input,
+        (Some(_), _) => true,
     }
+}
 
-    fn pair<T: FromStr>(&mut self) -> (T, T)
-    where
-        T: FromStr,
-        T::Err: Debug,
-    {
-        (self.next(), self.next())
-    }
+// The frozen graph from `cache`, built from the timetable and saved there first when it isn't fresh
+fn cached(cache: &Path, path: Option<&Path>, format: Option<Format>) -> CsrGraph {
+    let graph = if fresh(cache, path) {
+        CsrGraph::load(cache)
+    } else {
+        let graph = load(path, format).freeze();
+        graph.save(cache).map(|()| graph)
+    };
+    graph.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(1)
+    })
+}
 
-    fn collect<T: FromStr>(self) -> Vec<T>
-    where
-        T: FromStr,
-        T::Err: Debug,
-    {
-        self.split.map(|d| d.parse::<T>().unwrap()).collect::<Vec<T>>()
+// Exits with 1 when anything is wrong with the answer
+fn check<G: Timetable<Time = u32>>(graph: &G, path: &Path, buffer: u32, output: &str) {
+    let problems = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| Json::parse(&text))
+        .and_then(|answer| audit(graph, &answer, buffer))
+        .unwrap_or_else(|e| {
+            eprintln!("{}: {}", path.display(), e);
+            exit(1)
+        });
+    match output {
+        "json" => println!(
+            "{}",
+            Json::object([("valid", Json::Bool(problems.is_empty())), ("problems", Json::Array(problems.iter().map(|p| p.as_str().into()).collect()))])
+        ),
+        _ if problems.is_empty() => println!("ok"),
+        _ => problems.iter().for_each(|problem| println!("{}", problem)),
+    }
+    if !problems.is_empty() {
+        exit(1)
     }
 }
 
-type Vertex = u32;
-
-#[derive(Clone, Debug)]
-struct Edge {
-    to: Vertex,           // Destination location
-    weight: i32,          // Negative weight for maximum path
-    departure_time: u32,  // Departure time of the destination bus line
+// The stop count, after checking the stops asked for are among them
+fn known(graph: &impl Timetable, from: &Option<Vec<Vertex>>, to: &Option<Vec<Vertex>>) -> Vertex {
+    let n = graph.stop_count() as Vertex;
+    if let Some(stop) = from.iter().chain(to).flatten().find(|&&stop| stop >= n) {
+        eprintln!("there is no stop {}, the timetable has {}", stop, n);
+        exit(1)
+    }
+    n
 }
 
-struct Graph {
-    adjacency: Vec<Vec<Edge>>,  // Adjacency list for the graph
-    in_degree: Vec<u32>,        // Track in-degree for topological sorting
+// A question about one timetable, as the flags asked it
+struct Query {
+    command: String,
+    from: Option<Vec<Vertex>>,
+    to: Option<Vec<Vertex>>,
+    at: u32,
+    date: Option<Date>,
+    within: Option<u32>,
+    weights: Generalized,
+    exclusions: Exclusions,
 }
 
-impl Graph {
-    fn new(n: usize) -> Graph {
-        Graph {
-            adjacency: vec![vec![]; 10000],
-            in_degree: vec![0; 10000],
+fn ask<G: Timetable<Time = u32>>(graph: &G, query: Query) -> Report {
+    let Query { command, from, to, at, date, within, weights, exclusions } = query;
+    let n = known(graph, &from, &to);
+    // Mercator is always 0 and home is always the last one (l-1)
+    match command.as_str() {
+        "earliest" if date.is_some() => {
+            let (from, to) = (single(&from, 0, "--from"), single(&to, n - 1, "--to"));
+            let departure = DateTime::new(date.unwrap(), at);
+            Report::collect(|stats| Answer::Dated {
+                from,
+                to,
+                departure,
+                journey: earliest::dijkstra_on(graph, from, to, departure, &exclusions, stats),
+            })
         }
-    }
-
-    fn add_edge(&mut self, from: Vertex, to: Vertex, weight: i32, departure_time: u32) {
-        self.adjacency[from as usize].push(Edge { to, weight, departure_time });
-        self.in_degree[to as usize] += 1;
-    }
-
-    fn topological_sort(&mut self) -> Vec<Vertex> {
-        let mut queue = VecDeque::new();
-        let mut result = Vec::new();
-
-        // Collect nodes with no incoming edges (in-degree 0)
-        for (i, &in_deg) in self.in_degree.iter().enumerate() {
-            if in_deg == 0 {
-                queue.push_back(i as u32);
-            }
+        "cost" => {
+            let (from, to) = (single(&from, 0, "--from"), single(&to, n - 1, "--to"));
+            Report::collect(|stats| Answer::cost(graph, from, to, at, weights, &exclusions, stats))
         }
-
-        // Process nodes in topological order
-        while let Some(vertex) = queue.pop_front() {
-            result.push(vertex);
-
-            for edge in &self.adjacency[vertex as usize] {
-                let to = edge.to as usize;
-                self.in_degree[to] -= 1;
-                if self.in_degree[to] == 0 {
-                    queue.push_back(edge.to);
-                }
-            }
+        "profile" => {
+            let (from, to) = (single(&from, 0, "--from"), single(&to, n - 1, "--to"));
+            Report::collect(|stats| Answer::Profile { from, to, journeys: profile::profile(graph, from, to, &exclusions, stats) })
         }
-
-        result
-    }
-
-    // DAG Shortest Path with Negative Weights (using Topological Sort)
-    fn dag_shortest_path(&mut self, start: Vertex) -> Vec<i32> {
-        let n = self.adjacency.len();
-        let mut dist = vec![i32::MIN; n]; // Initialize distances to negative infinity
-        dist[start as usize] = 0; // Distance to start is 0
-
-        let topological_order = self.topological_sort();
-
-        // Relax edges in topological order
-        for vertex in topological_order {
-            for edge in &self.adjacency[vertex as usize] {
-                let new_dist = dist[vertex as usize] + edge.weight;
-                if new_dist > dist[edge.to as usize] {
-                    dist[edge.to as usize] = new_dist;
-                }
-            }
+        "matrix" => {
+            let origins = from.unwrap_or_else(|| (0..n).collect());
+            let destinations = to.unwrap_or_else(|| (0..n).collect());
+            Report::collect(|stats| Answer::Matrix {
+                at,
+                arrivals: profile::matrix(graph, &origins, &destinations, at, &exclusions, stats),
+                origins,
+                destinations,
+            })
         }
-
-        dist
-    }
-}
-
-impl Display for Graph {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for (vertex, edges) in self.adjacency.iter().enumerate() {
-            writeln!(f, "Vertex {}: ", vertex)?;  // Display each vertex
-            for edge in edges {
-                writeln!(
-                    f,
-                    "  -> to: {}, weight: {}, departure time: {}",
-                    edge.to, edge.weight, edge.departure_time
-                )?; // Show edges with departure time
+        "isochrone" => {
+            let from = single(&from, 0, "--from");
+            let within = within.unwrap_or_else(|| usage("isochrone needs --within"));
+            Report::collect(|stats| Answer::Isochrone {
+                from,
+                at,
+                within,
+                reached: profile::isochrone(graph, from, at, within, &exclusions, stats),
+            })
+        }
+        name => {
+            let (from, to) = (single(&from, 0, "--from"), single(&to, n - 1, "--to"));
+            if exclusions.is_empty() {
+                Report::collect(|stats| Answer::route(graph, name, from, to, at, &exclusions, stats).unwrap())
+            } else {
+                // With something closed or cancelled, show what that changes
+                Report::collect(|stats| Answer::what_if(graph, name, from, to, at, &exclusions, stats).unwrap())
             }
         }
-        Ok(())
     }
 }
 
 fn main() {
-    let input = stdin();
-    let mut input = Input::new(BufReader::new(input.lock()));
-
-    let (b, l) = input.line().pair::<u32>();
-    const MERCATOR: u32 = 0;
-    let home: u32 = l - 1; // Home location is always the last one (l-1)
-    let mut graph = Graph::new(l as usize);
-
-    // We will store the buses arriving at each location
-    let mut location_buses: HashMap<u32, Vec<(u32, u32)>> = HashMap::new(); // Location -> (bus_line, departure_time)
-
-    // Process the bus lines
-    for bus_line in 0..b {
-        let locations = input
-            .line()
-            .collect::<Vertex>();
-        let departures = input
-            .line()
-            .collect::<u32>();
-
-        for (i, &location) in locations.iter().enumerate() {
-            let departure_time = departures[i];
-            location_buses
-                .entry(location)
-                .or_insert_with(Vec::new)
-                .push((bus_line, departure_time));
+    let mut args = std::env::args().skip(1);
+    let command = args.next().unwrap_or_else(|| usage("missing command"));
+    if command == "generate" {
+        return generate(args);
+    }
+    let (mut path, mut format, mut output, mut cache, mut updates) = (None, None, "text".to_string(), None, None);
+    let (mut from, mut to, mut at, mut date, mut within) = (None, None, None, None, None);
+    let (mut listen, mut stats) = ("127.0.0.1:8080".to_string(), false);
+    let (mut dot, mut highlight) = (DotOptions::default(), None);
+    let (mut answer, mut buffer) = (None, 0);
+    let mut weights = Generalized::default();
+    let mut exclusions = Exclusions::none();
+    while let Some(flag) = args.next() {
+        let switch = match flag.as_str() {
+            "--stats" => Some(&mut stats),
+            "--color" => Some(&mut dot.color_by_line),
+            "--collapse" => Some(&mut dot.collapse),
+            _ => None,
+        };
+        if let Some(switch) = switch {
+            *switch = true;
+            continue;
+        }
+        let value = args.next().unwrap_or_else(|| usage(&format!("{} needs a value", flag)));
+        match flag.as_str() {
+            "--input" => path = Some(PathBuf::from(value)),
+            "--format" => format = Some(value.parse::<Format>().unwrap_or_else(|e| usage(&e))),
+            "--cache" => cache = Some(PathBuf::from(value)),
+            "--updates" => updates = Some(PathBuf::from(value)),
+            "--from" => from = Some(ids(&value, "stop")),
+            "--to" => to = Some(ids(&value, "stop")),
+            "--at" => at = Some(minutes(&value).unwrap_or_else(|| usage(&format!("'{}' is not minutes or H:MM", value)))),
+            "--date" => date = Some(value.parse::<Date>().unwrap_or_else(|e| usage(&e))),
+            "--within" => within = Some(value.parse::<u32>().unwrap_or_else(|_| usage(&format!("'{}' is not minutes", value)))),
+            "--wait" => weights.wait = value.parse().unwrap_or_else(|e: String| usage(&e)),
+            "--ride" => weights.ride = value.parse().unwrap_or_else(|e: String| usage(&e)),
+            "--transfer" => weights.transfer = value.parse().unwrap_or_else(|e: String| usage(&e)),
+            "--walk" => usage(NO_WALKING),
+            "--closed" => exclusions = ids(&value, "stop").into_iter().fold(exclusions, Exclusions::close_stop),
+            "--cancel-line" => exclusions = ids(&value, "line").into_iter().fold(exclusions, Exclusions::cancel_line),
+            "--cancel-trip" => exclusions = ids(&value, "trip").into_iter().fold(exclusions, Exclusions::cancel_trip),
+            "--highlight" => highlight = Some(value),
+            "--listen" => listen = value,
+            "--answer" => answer = Some(PathBuf::from(value)),
+            "--buffer" => buffer = value.parse::<u32>().unwrap_or_else(|_| usage(&format!("'{}' is not minutes", value))),
+            "--output" => output = value,
+            "--log" => logger::init(&value).unwrap_or_else(|e| usage(&e)),
+            _ => usage(&format!("unknown flag {}", flag)),
         }
     }
+    if !["cost", "dot", "profile", "matrix", "isochrone", "repl", "serve", "check"].contains(&command.as_str()) && solvers::named::<Graph>(&command).is_none() {
+        usage(&format!("unknown command {}", command));
+    }
+    if command == "repl" && path.is_none() {
+        usage("repl reads its queries from stdin, so the timetable has to come from --input");
+    }
+    if command == "check" && answer.is_none() {
+        usage("check needs the --answer to check");
+    }
+    if ["repl", "serve", "dot"].contains(&command.as_str()) && cache.is_some() {
+        usage(&format!("{} works on the timetable itself, not on a --cache", command));
+    }
+    if updates.is_some() && cache.is_some() {
+        usage("--updates change the timetable, so they can't be applied to a --cache");
+    }
+    if at.is_some() && !["earliest", "cost", "matrix", "isochrone"].contains(&command.as_str()) {
+        usage(&format!("{} doesn't take an --at, only earliest, cost, matrix and isochrone do", command));
+    }
+    let at = at.unwrap_or(0);
+    if date.is_some() && command != "earliest" {
+        usage("only earliest takes a --date");
+    }
+    if output != "text" && output != "json" {
+        usage(&format!("unknown output format {}", output));
+    }
 
-    // Now, process the transfers between bus lines at each location
-    for (&location, buses) in location_buses.iter() {
-        // Sort buses at this location by departure time to ensure correct transfer times
-        let mut sorted_buses = buses.clone();
-        sorted_buses.sort_by_key(|&(_, time)| time);
-
-        // Loop through each pair of buses at this location and check if they belong to different bus lines
-        for i in 0..sorted_buses.len() {
-            for j in i + 1..sorted_buses.len() {
-                let (bus_a, time_a) = sorted_buses[i];
-                let (bus_b, time_b) = sorted_buses[j];
-
-                // Only calculate transfer time between different bus lines
-                if bus_a != bus_b && time_a < time_b {
-                    // Calculate the transfer time between the two bus lines
-                    let transfer_time = time_b - time_a; // Waiting time between buses
-
-                    // Add edge between the locations of the different bus lines
-                    // The edge represents a transfer between two different bus lines at the same location
-                    // We should only add the edge between different locations, not to the same location
-                    graph.add_edge(location, location + 1, -(transfer_time as i32), time_b); // Example connection
-                }
-            }
+    if let Some(cache) = cache {
+        let graph = cached(&cache, path.as_deref(), format);
+        if command == "check" {
+            return check(&graph, answer.as_deref().unwrap(), buffer, &output);
         }
+        let report = ask(&graph, Query { command, from, to, at, date, within, weights, exclusions });
+        return print(&report, &output, stats);
     }
 
-    // Print the graph to verify that edges are being added correctly
-    println!("Graph after adding edges:");
-    println!("{graph}");
-
-    // Perform DAG Shortest Path (Maximum Path)
-    let dist = graph.dag_shortest_path(0); // Start from vertex 0 (can be adjusted)
-
-    println!("Maximum transfer times from start: {:?}", dist);
+    let mut graph = load(path.as_deref(), format);
+    if let Some(updates) = updates {
+        graph.apply_file(&updates).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            exit(1)
+        });
+    }
+    if command == "repl" {
+        Session::new(graph).run(stdin().lock(), stdout()).unwrap();
+        return;
+    }
+    if command == "serve" {
+        let listener = TcpListener::bind(&listen).unwrap_or_else(|e| {
+            eprintln!("{}: {}", listen, e);
+            exit(1)
+        });
+        eprintln!("serving {} stops on http://{}", graph.stop_count(), listen);
+        serve(listener, Arc::new(graph)).unwrap();
+        return;
+    }
+    if command == "check" {
+        return check(&graph, answer.as_deref().unwrap(), buffer, &output);
+    }
+    if command == "dot" {
+        let journey = highlight.map(|name| {
+            let (solve, _) = solvers::named::<Graph>(&name).unwrap_or_else(|| usage(&format!("unknown solver {}", name)));
+            let n = known(&graph, &from, &to);
+            let (from, to) = (single(&from, 0, "--from"), single(&to, n - 1, "--to"));
+            solve(&graph, from, to, &exclusions, &mut SearchStats::default())
+        });
+        dot.highlight = journey.as_ref().and_then(Option::as_ref);
+        return print!("{}", graph.to_dot(&dot));
+    }
+    let report = ask(&graph, Query { command, from, to, at, date, within, weights, exclusions });
+    print(&report, &output, stats);
+}
 
-    // Check the maximum transfer time to home (l-1)
-    let home_time = dist[home as usize];
-    if home_time == i32::MIN {
-        println!("No path found to home.");
-    } else {
-        println!("Maximum transfer time to home ({}): {}", home, home_time);
+fn print(report: &Report, output: &str, stats: bool) {
+    match output {
+        "json" => println!("{}", report.to_json()),
+        _ => println!("{}", report),
+    }
+    if stats {
+        eprintln!("{}", report.stats);
     }
 }
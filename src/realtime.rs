@@ -0,0 +1,116 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::graph::{Graph, TripId};
+use crate::input::Input;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Status {
+    Delayed(i32), // Seconds behind schedule, negative when running early
+    Cancelled,
+}
+
+// A real-time message about one trip: from `stop_index` onwards it runs with `status`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Update {
+    pub trip: TripId,
+    pub stop_index: u32,
+    pub status: Status,
+}
+
+// "<trip> <stop index> <delay seconds>" or "<trip> <stop index> cancelled"
+impl FromStr for Update {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        let [trip, stop_index, status] = fields[..] else {
+            return Err(format!("update '{}' needs a trip, stop index and delay", s));
+        };
+
+        Ok(Update {
+            trip: number(trip, s)?,
+            stop_index: number(stop_index, s)?,
+            status: match status {
+                "cancelled" => Status::Cancelled,
+                delay => Status::Delayed(number(delay, s)?),
+            },
+        })
+    }
+}
+
+fn number<T: FromStr>(field: &str, update: &str) -> Result<T, String> {
+    field.parse().map_err(|_| format!("invalid number '{}' in update '{}'", field, update))
+}
+
+impl Graph {
+    /*
+     * Applies a delay or cancellation to an already built graph. A delay holds for the given stop and
+     * every stop after it, measured against the planned timetable, so sending the same update twice
+     * changes nothing and a later update for a further stop overrides the earlier one from there on.
+     * A cancellation ends the trip at the given stop: it still arrives there, but leaves no more.
+     */
+    pub fn apply(&mut self, update: &Update) -> Result<(), String> {
+        let trip = self
+            .trips
+            .get(update.trip as usize)
+            .ok_or_else(|| format!("unknown trip {}", update.trip))?;
+        if trip.stops.is_empty() {
+            return Err(format!("trip {} is a frequency line, its runs can't be updated", update.trip));
+        }
+        let from = update.stop_index as usize;
+        if from >= trip.stops.len() {
+            return Err(format!("trip {} has no stop {}", update.trip, update.stop_index));
+        }
+
+        let legs = trip.stops.iter().zip(&trip.legs).map(|(&stop, &leg)| (stop as usize, leg)).collect::<Vec<_>>();
+        match update.status {
+            Status::Cancelled => {
+                for &(stop, leg) in &legs[from..] {
+                    self.adjacency[stop][leg].cancelled = true;
+                }
+            }
+            Status::Delayed(seconds) => {
+                // Rounded up, we'd rather have someone wait a minute than miss the bus
+                let minutes = seconds.div_euclid(60) + i32::from(seconds.rem_euclid(60) > 0);
+                // Checked before touching the trip: delayed past the end of time it would wrap round to running early
+                let delayed = |planned: u32| {
+                    u32::try_from((planned as i64 + minutes as i64).max(0))
+                        .map_err(|_| format!("trip {} delayed {} minutes would run past the end of time", update.trip, minutes))
+                };
+                let delayed = trip.schedule.iter().map(|&planned| delayed(planned)).collect::<Result<Vec<_>, _>>()?;
+
+                for (i, &(stop, leg)) in legs.iter().enumerate().skip(from) {
+                    self.adjacency[stop][leg].departure_time = delayed[i];
+                }
+                // Every leg arrives where the next one departs, the one into `from` included
+                for (i, &(stop, leg)) in legs.iter().enumerate().skip(from.saturating_sub(1)) {
+                    let edge = &mut self.adjacency[stop][leg];
+                    edge.weight = delayed[i + 1].saturating_sub(edge.departure_time);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Reads one update per line until the input runs out, see Update::from_str.
+    // Stops at the first bad update, keeping the ones before it applied.
+    pub fn apply_all<B: BufRead>(&mut self, input: &mut Input<B>) -> Result<usize, String> {
+        let mut applied = 0;
+        let context = |applied: usize| move |e: String| format!("update {}: {}", applied + 1, e);
+        while let Some(line) = input.try_line().map_err(context(applied))? {
+            let update = line.split.collect::<Vec<_>>().join(" ");
+            update.parse::<Update>().and_then(|update| self.apply(&update)).map_err(context(applied))?;
+            applied += 1;
+        }
+        Ok(applied)
+    }
+
+    // apply_all on a file of updates, as given to --updates and the REPL's apply
+    pub fn apply_file(&mut self, path: &Path) -> Result<usize, String> {
+        let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.apply_all(&mut Input::new(BufReader::new(file))).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
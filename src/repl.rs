@@ -0,0 +1,202 @@
+use std::fmt::Write as _;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use crate::calendar::{duration, minutes};
+use crate::exclusions::{Exclusions, WhatIf};
+use crate::graph::{Graph, LineId, Timetable, TripId, Vertex};
+use crate::solvers::stats::SearchStats;
+use crate::solvers::{self, earliest, profile, Solver};
+
+const HELP: &str = "\
+route FROM TO [at TIME]          earliest journey, setting off at TIME (minutes or H:MM, 0 by default)
+isochrone FROM WITHIN [at TIME]  stops reached within WITHIN (like 30m or 1h15m)
+closed STOP                      pretend STOP is closed for the queries that follow
+open STOP                        open it again
+cancel line LINE | trip TRIP     pretend a line or a single run of it doesn't run
+restore line LINE | trip TRIP    run it again
+apply FILE                       delays and cancellations from FILE, one \"TRIP STOP SECONDS|cancelled\" a line
+whatif FROM TO [SOLVER]          the journey by SOLVER (earliest by default) with and without what is
+                                 closed or cancelled, and how it changed; shown again after every change
+show line LINE                   every run of a line and its stops
+stats                            what the searches of the last query went through
+help                             this
+quit                             done";
+
+// A loaded timetable answering one query after the other, with what is closed or cancelled so far
+pub struct Session {
+    pub graph: Graph,
+    pub exclusions: Exclusions,
+    pub stats: Option<SearchStats>, // Of the last route or isochrone
+    whatif: Option<(Vertex, Vertex, Solver)>, // The last whatif query, to show again when the exclusions change
+}
+
+impl Session {
+    pub fn new(graph: Graph) -> Session {
+        Session { graph, exclusions: Exclusions::none(), stats: None, whatif: None }
+    }
+
+    // Reads commands until quit or the end of input, answers and errors alike go to `output`
+    pub fn run<B: BufRead, W: Write>(&mut self, input: B, mut output: W) -> std::io::Result<()> {
+        write!(output, "> ")?;
+        output.flush()?;
+        for line in input.lines() {
+            let line = line?;
+            match line.trim() {
+                "" => {}
+                "quit" | "exit" => return Ok(()),
+                command => match self.execute(command) {
+                    Ok(answer) => writeln!(output, "{}", answer)?,
+                    Err(e) => writeln!(output, "error: {}", e)?,
+                },
+            }
+            write!(output, "> ")?;
+            output.flush()?;
+        }
+        writeln!(output)
+    }
+
+    pub fn execute(&mut self, command: &str) -> Result<String, String> {
+        let words = command.split_whitespace().collect::<Vec<_>>();
+        match words[..] {
+            ["help"] => Ok(HELP.to_string()),
+            ["route", from, to, ref rest @ ..] => {
+                let (from, to, at) = (self.stop(from)?, self.stop(to)?, at(rest)?);
+                let stats = self.stats.insert(SearchStats::default());
+                match earliest::dijkstra_from(&self.graph, from, to, at, &self.exclusions, stats) {
+                    Some(journey) => Ok(journey.to_string()),
+                    None => Ok(format!("No path found from {} to {}.", from, to)),
+                }
+            }
+            ["isochrone", from, within, ref rest @ ..] => {
+                let from = self.stop(from)?;
+                let within = duration(within).ok_or_else(|| format!("'{}' is not a duration like 30m", within))?;
+                let at = at(rest)?;
+                let stats = self.stats.insert(SearchStats::default());
+                let reached = profile::isochrone(&self.graph, from, at, within, &self.exclusions, stats);
+                let mut answer = format!("{} stops within {} minutes of {} at {}", reached.len(), within, from, at);
+                for (stop, arrival) in reached {
+                    write!(answer, "\n  {} at {}", stop, arrival).unwrap();
+                }
+                Ok(answer)
+            }
+            ["stats"] => self.stats.map(|stats| stats.to_string()).ok_or_else(|| "no query yet".to_string()),
+            ["closed", stop] => {
+                let stop = self.stop(stop)?;
+                self.exclusions.stops.insert(stop);
+                Ok(self.changed(format!("stop {} is closed", stop)))
+            }
+            ["open", stop] => {
+                let stop = self.stop(stop)?;
+                self.exclusions.stops.remove(&stop);
+                Ok(self.changed(format!("stop {} is open", stop)))
+            }
+            ["cancel", "line", line] => {
+                let line = self.line(line)?;
+                self.exclusions.lines.insert(line);
+                Ok(self.changed(format!("line {} is cancelled", line)))
+            }
+            ["cancel", "trip", trip] => {
+                let trip = self.trip(trip)?;
+                self.exclusions.trips.insert(trip);
+                Ok(self.changed(format!("trip {} is cancelled", trip)))
+            }
+            ["restore", "line", line] => {
+                let line = self.line(line)?;
+                self.exclusions.lines.remove(&line);
+                Ok(self.changed(format!("line {} runs", line)))
+            }
+            ["restore", "trip", trip] => {
+                let trip = self.trip(trip)?;
+                self.exclusions.trips.remove(&trip);
+                Ok(self.changed(format!("trip {} runs", trip)))
+            }
+            ["apply", path] => {
+                let applied = self.graph.apply_file(Path::new(path))?;
+                Ok(self.changed(format!("applied {} updates", applied)))
+            }
+            ["whatif", from, to, ref rest @ ..] => {
+                let (from, to) = (self.stop(from)?, self.stop(to)?);
+                let solve = match rest {
+                    [] => earliest::dijkstra,
+                    [name] => solvers::named::<Graph>(name).ok_or_else(|| format!("there is no solver '{}'", name))?.0,
+                    _ => return Err(format!("expected a solver but got '{}'", rest.join(" "))),
+                };
+                self.whatif = Some((from, to, solve));
+                Ok(WhatIf::run(&self.graph, from, to, &self.exclusions, solve).to_string())
+            }
+            ["show", "line", line] => {
+                let line = line.parse::<LineId>().map_err(|_| format!("'{}' is not a line", line))?;
+                self.show_line(line)
+            }
+            _ => Err(format!("don't know '{}', try help", command)),
+        }
+    }
+
+    // What happened, and what it does to the last whatif query
+    fn changed(&self, message: String) -> String {
+        match self.whatif {
+            Some((from, to, solve)) => format!("{}\n{}", message, WhatIf::run(&self.graph, from, to, &self.exclusions, solve)),
+            None => message,
+        }
+    }
+
+    fn line(&self, line: &str) -> Result<LineId, String> {
+        line.parse::<LineId>()
+            .ok()
+            .filter(|&line| self.graph.trips.iter().any(|trip| trip.line == line))
+            .ok_or_else(|| format!("there is no line '{}'", line))
+    }
+
+    fn trip(&self, trip: &str) -> Result<TripId, String> {
+        trip.parse::<TripId>()
+            .ok()
+            .filter(|&trip| (trip as usize) < self.graph.trips.len())
+            .ok_or_else(|| format!("there is no trip '{}'", trip))
+    }
+
+    fn stop(&self, stop: &str) -> Result<Vertex, String> {
+        stop.parse::<Vertex>()
+            .ok()
+            .filter(|&stop| (stop as usize) < self.graph.stop_count())
+            .ok_or_else(|| format!("there is no stop '{}'", stop))
+    }
+
+    // Every run of the line as its stops with the times the bus leaves them, delays included
+    fn show_line(&self, line: LineId) -> Result<String, String> {
+        let mut answer = String::new();
+        for (id, trip) in self.graph.trips.iter().enumerate().filter(|(_, trip)| trip.line == line) {
+            write!(answer, "trip {} ({})", id, trip.name).unwrap();
+            if trip.stops.is_empty() {
+                // A frequency line, its edges know where it goes
+                let edges = self.graph.frequencies.iter().enumerate().flat_map(|(from, edges)| {
+                    edges.iter().filter(move |edge| edge.trip == id as u32).map(move |edge| (from, edge))
+                });
+                for (from, edge) in edges {
+                    write!(answer, "\n  {} -> {}  every {} from {} to {}", from, edge.to, edge.headway, edge.first.saturating_add(edge.offset), edge.last_departure()).unwrap();
+                }
+            } else {
+                for (&stop, &leg) in trip.stops.iter().zip(&trip.legs) {
+                    let edge = &self.graph.adjacency[stop as usize][leg];
+                    let cancelled = if edge.cancelled { "  cancelled" } else { "" };
+                    write!(answer, "\n  {} -> {}  {} - {}{}", stop, edge.to, edge.departure_time, edge.arrival(), cancelled).unwrap();
+                }
+            }
+            answer.push('\n');
+        }
+        if answer.is_empty() {
+            return Err(format!("there is no line {}", line));
+        }
+        answer.pop();
+        Ok(answer)
+    }
+}
+
+// An optional "at TIME" at the end of a command
+fn at(rest: &[&str]) -> Result<u32, String> {
+    match rest {
+        [] => Ok(0),
+        ["at", time] => minutes(time).ok_or_else(|| format!("'{}' is not minutes or H:MM", time)),
+        _ => Err(format!("expected 'at TIME' but got '{}'", rest.join(" "))),
+    }
+}
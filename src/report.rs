@@ -0,0 +1,317 @@
+use std::fmt::{Display, Formatter};
+
+use crate::calendar::DateTime;
+use crate::exclusions::{Exclusions, WhatIf};
+use crate::graph::{Timetable, Vertex};
+use crate::journey::{Journey, Leg};
+use crate::json::Json;
+use crate::solvers::cost::{Breakdown, Generalized};
+use crate::solvers::label;
+use crate::solvers::stats::SearchStats;
+use crate::solvers::{self, earliest};
+
+// An answer to any of the questions the command line, the REPL and the server can be asked
+#[derive(Clone, Debug)]
+pub enum Answer {
+    Route {
+        solver: String,
+        from: Vertex,
+        to: Vertex,
+        value: Option<u32>, // What the solver optimises: the arrival time, the time spent waiting or the transfers
+        journey: Option<Journey>,
+    },
+    WhatIf {
+        solver: String,
+        from: Vertex,
+        to: Vertex,
+        what_if: WhatIf, // The journey by the solver with and without the exclusions
+    },
+    Dated {
+        from: Vertex,
+        to: Vertex,
+        departure: DateTime,
+        journey: Option<Journey<DateTime>>, // Earliest, taking only trips that run on their service day
+    },
+    Cost {
+        from: Vertex,
+        to: Vertex,
+        at: u32,
+        weights: Generalized,
+        journey: Option<Journey>,
+        breakdown: Option<Breakdown>,
+    },
+    Profile {
+        from: Vertex,
+        to: Vertex,
+        journeys: Vec<Journey>,
+    },
+    Isochrone {
+        from: Vertex,
+        at: u32,
+        within: u32,
+        reached: Vec<(Vertex, u32)>,
+    },
+    Matrix {
+        at: u32,
+        origins: Vec<Vertex>,
+        destinations: Vec<Vertex>,
+        arrivals: Vec<Vec<Option<u32>>>,
+    },
+}
+
+impl Answer {
+    // The best journey by the named solver, None if there's no such solver. Only earliest sets off
+    // at `at`, the others answer the assignments and always start at 0.
+    pub fn route<G: Timetable<Time = u32>>(
+        graph: &G,
+        solver: &str,
+        from: Vertex,
+        to: Vertex,
+        at: u32,
+        exclusions: &Exclusions,
+        stats: &mut SearchStats,
+    ) -> Option<Answer> {
+        let (_, value) = solvers::named::<G>(solver)?;
+        let journey = journey(graph, solver, from, to, at, exclusions, stats)?;
+        Some(Answer::Route { solver: solver.to_string(), from, to, value: journey.as_ref().map(value), journey })
+    }
+
+    // The same route on the whole network and with `exclusions` in place, None if there's no such solver
+    pub fn what_if<G: Timetable<Time = u32>>(
+        graph: &G,
+        solver: &str,
+        from: Vertex,
+        to: Vertex,
+        at: u32,
+        exclusions: &Exclusions,
+        stats: &mut SearchStats,
+    ) -> Option<Answer> {
+        let baseline = journey(graph, solver, from, to, at, &Exclusions::none(), stats)?;
+        let restricted = journey(graph, solver, from, to, at, exclusions, stats)?;
+        Some(Answer::WhatIf { solver: solver.to_string(), from, to, what_if: WhatIf { baseline, restricted } })
+    }
+
+    // The journey with the least generalized cost by `weights`, setting off at `at`
+    pub fn cost<G: Timetable<Time = u32>>(
+        graph: &G,
+        from: Vertex,
+        to: Vertex,
+        at: u32,
+        weights: Generalized,
+        exclusions: &Exclusions,
+        stats: &mut SearchStats,
+    ) -> Answer {
+        let journey = label::search(graph, from, to, at, exclusions, &weights, stats).map(|(journey, _)| journey);
+        let breakdown = journey.as_ref().map(|journey| weights.breakdown(journey));
+        Answer::Cost { from, to, at, weights, journey, breakdown }
+    }
+}
+
+// The journey the named solver finds, None if there's no such solver
+fn journey<G: Timetable<Time = u32>>(
+    graph: &G,
+    solver: &str,
+    from: Vertex,
+    to: Vertex,
+    at: u32,
+    exclusions: &Exclusions,
+    stats: &mut SearchStats,
+) -> Option<Option<Journey>> {
+    let (solve, _) = solvers::named::<G>(solver)?;
+    Some(match solver {
+        "earliest" => earliest::dijkstra_from(graph, from, to, at, exclusions, stats),
+        _ => solve(graph, from, to, exclusions, stats),
+    })
+}
+
+// When a dated journey gets home. Journey::arrival needs times that add up, which dates don't
+fn arrival(journey: &Journey<DateTime>) -> DateTime {
+    journey.legs.last().map_or(journey.start, |leg| leg.arrival)
+}
+
+// Like Journey::to_json, with every time written out as a date and time of day
+fn dated_json(journey: &Journey<DateTime>) -> Json {
+    let time = |time: DateTime| Json::from(time.to_string().as_str());
+    Json::object([
+        ("origin", journey.origin.into()),
+        ("destination", journey.legs.last().map_or(journey.origin, |leg| leg.to).into()),
+        ("start", time(journey.start)),
+        ("arrival", time(arrival(journey))),
+        (
+            "legs",
+            Json::Array(
+                journey
+                    .legs
+                    .iter()
+                    .map(|leg| {
+                        Json::object([
+                            ("from", leg.from.into()),
+                            ("to", leg.to.into()),
+                            ("departure", time(leg.departure)),
+                            ("arrival", time(leg.arrival)),
+                            ("trip", leg.trip.into()),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+// An answer with what the searches went through to find it
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub answer: Answer,
+    pub stats: SearchStats,
+}
+
+impl Report {
+    // Runs the searches behind an answer, collecting their stats. They time themselves, see SearchStats::timed
+    pub fn collect(query: impl FnOnce(&mut SearchStats) -> Answer) -> Report {
+        let mut stats = SearchStats::default();
+        let answer = query(&mut stats);
+        Report { answer, stats }
+    }
+
+    pub fn to_json(&self) -> Json {
+        let mut fields = match &self.answer {
+            Answer::Route { solver, from, to, value, journey } => vec![
+                ("solver", solver.as_str().into()),
+                ("from", (*from).into()),
+                ("to", (*to).into()),
+                ("answer", (*value).into()),
+                ("journey", journey.as_ref().map_or(Json::Null, Journey::to_json)),
+            ],
+            Answer::WhatIf { solver, from, to, what_if } => vec![
+                ("solver", solver.as_str().into()),
+                ("from", (*from).into()),
+                ("to", (*to).into()),
+                ("baseline", what_if.baseline.as_ref().map_or(Json::Null, Journey::to_json)),
+                ("restricted", what_if.restricted.as_ref().map_or(Json::Null, Journey::to_json)),
+                ("delay", what_if.arrival_delay().map_or(Json::Null, |delay| Json::Number(delay as f64))),
+                ("removed", Json::Array(what_if.removed().iter().map(Leg::to_json).collect())),
+                ("added", Json::Array(what_if.added().iter().map(Leg::to_json).collect())),
+            ],
+            Answer::Dated { from, to, departure, journey } => vec![
+                ("from", (*from).into()),
+                ("to", (*to).into()),
+                ("departure", departure.to_string().as_str().into()),
+                ("answer", journey.as_ref().map_or(Json::Null, |journey| arrival(journey).to_string().as_str().into())),
+                ("journey", journey.as_ref().map_or(Json::Null, dated_json)),
+            ],
+            Answer::Cost { from, to, at, weights, journey, breakdown } => vec![
+                ("from", (*from).into()),
+                ("to", (*to).into()),
+                ("at", (*at).into()),
+                ("weights", weights.to_json()),
+                ("answer", breakdown.map(|breakdown| breakdown.cost()).into()),
+                ("breakdown", breakdown.as_ref().map_or(Json::Null, Breakdown::to_json)),
+                ("journey", journey.as_ref().map_or(Json::Null, Journey::to_json)),
+            ],
+            Answer::Profile { from, to, journeys } => vec![
+                ("from", (*from).into()),
+                ("to", (*to).into()),
+                ("profile", Json::Array(journeys.iter().map(Journey::to_json).collect())),
+            ],
+            Answer::Isochrone { from, at, within, reached } => vec![
+                ("from", (*from).into()),
+                ("at", (*at).into()),
+                ("within", (*within).into()),
+                (
+                    "reached",
+                    Json::Array(
+                        reached
+                            .iter()
+                            .map(|&(stop, arrival)| Json::object([("stop", stop.into()), ("arrival", arrival.into())]))
+                            .collect(),
+                    ),
+                ),
+            ],
+            Answer::Matrix { at, origins, destinations, arrivals } => vec![
+                ("at", (*at).into()),
+                ("origins", Json::Array(origins.iter().map(|&stop| stop.into()).collect())),
+                ("destinations", Json::Array(destinations.iter().map(|&stop| stop.into()).collect())),
+                (
+                    "arrivals",
+                    Json::Array(arrivals.iter().map(|row| Json::Array(row.iter().map(|&arrival| arrival.into()).collect())).collect()),
+                ),
+            ],
+        };
+        fields.push(("stats", self.stats.to_json()));
+        Json::object(fields)
+    }
+}
+
+// Just the answer, like the assignments print it
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.answer {
+            Answer::Route { to, value, .. } => match value {
+                Some(value) => write!(f, "{}", value),
+                None => write!(f, "No path found to {}.", to),
+            },
+            Answer::WhatIf { what_if, .. } => write!(f, "{}", what_if),
+            Answer::Dated { to, journey, .. } => match journey {
+                Some(journey) => {
+                    write!(f, "{}", arrival(journey))?;
+                    for leg in &journey.legs {
+                        write!(f, "\n  {} -> {}  {} - {}  trip {}", leg.from, leg.to, leg.departure, leg.arrival, leg.trip)?;
+                    }
+                    Ok(())
+                }
+                None => write!(f, "No path found to {}.", to),
+            },
+            Answer::Cost { to, weights, breakdown, .. } => match breakdown {
+                Some(breakdown) => write!(
+                    f,
+                    "{} = waiting {} x {} + riding {} x {} + {} transfers x {}",
+                    breakdown.cost(),
+                    breakdown.wait,
+                    weights.wait,
+                    breakdown.ride,
+                    weights.ride,
+                    breakdown.transfers,
+                    weights.transfer
+                ),
+                None => write!(f, "No path found to {}.", to),
+            },
+            Answer::Profile { from, to, journeys } => {
+                if journeys.is_empty() {
+                    return write!(f, "No path found from {} to {}.", from, to);
+                }
+                for (i, journey) in journeys.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "leave {} arrive {} transfers {}", journey.start, journey.arrival(), journey.transfers())?;
+                }
+                Ok(())
+            }
+            Answer::Isochrone { reached, .. } => {
+                for (i, (stop, arrival)) in reached.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{} {}", stop, arrival)?;
+                }
+                Ok(())
+            }
+            Answer::Matrix { destinations, origins, arrivals, .. } => {
+                write!(f, "from\\to")?;
+                for destination in destinations {
+                    write!(f, "\t{}", destination)?;
+                }
+                for (origin, row) in origins.iter().zip(arrivals) {
+                    write!(f, "\n{}", origin)?;
+                    for arrival in row {
+                        match arrival {
+                            Some(arrival) => write!(f, "\t{}", arrival)?,
+                            None => write!(f, "\t-")?,
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
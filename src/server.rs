@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::calendar::{duration, minutes};
+use crate::exclusions::Exclusions;
+use crate::graph::{Graph, Timetable, Vertex};
+use crate::json::Json;
+use crate::report::{Answer, Report};
+use crate::solvers::cost::{Generalized, NO_WALKING};
+use crate::solvers::{self, profile};
+
+/*
+ * A small HTTP/1.1 server answering in JSON over a graph loaded once and shared by every request:
+ *
+ *   GET /health                                       {"status":"ok", ...}
+ *   GET /route?from=0&to=14&at=08:00&solver=earliest  like `pathfind earliest --output json`, solver
+ *                                                     and at being optional, at only for earliest
+ *   GET /cost?from=0&to=14&at=08:00&wait=2&ride=1.5   like `pathfind cost --output json`, at and the
+ *       &transfer=5                                   weights being optional
+ *   GET /profile?from=0&to=14                         like `pathfind profile --output json`
+ *   GET /isochrone?from=3&within=30m&at=08:00         like `pathfind isochrone --output json`
+ *
+ * Every connection gets its own thread and is closed after one response. Bad queries get a 400
+ * with {"error": "..."}. A client gets TIMEOUT to send each bit of its request and at most
+ * MAX_REQUEST_LINE and MAX_HEADERS bytes to send it in, so a slow or endless one can't keep a thread busy.
+ */
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_LINE: usize = 8 * 1024;
+const MAX_HEADERS: usize = 64 * 1024;
+
+struct Response {
+    status: &'static str,
+    body: Json,
+}
+
+impl Response {
+    fn ok(body: Json) -> Response {
+        Response { status: "200 OK", body }
+    }
+
+    fn error(status: &'static str, message: &str) -> Response {
+        Response { status, body: Json::object([("error", message.into())]) }
+    }
+}
+
+// Serves until the listener fails, which it shouldn't
+pub fn serve(listener: TcpListener, graph: Arc<Graph>) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let graph = Arc::clone(&graph);
+        thread::spawn(move || {
+            // Whoever hung up early doesn't need to hear about it
+            let _ = handle(stream, &graph);
+        });
+    }
+    Ok(())
+}
+
+// Reads a line of at most `limit` bytes into `line`, false when it goes on past that
+fn read_line(reader: &mut impl BufRead, line: &mut String, limit: usize) -> io::Result<bool> {
+    let read = reader.take(limit as u64).read_line(line)?;
+    Ok(read < limit || line.ends_with('\n'))
+}
+
+// Headers don't matter to us, but they have to be read before answering. False when there are too many.
+fn skip_headers(reader: &mut impl BufRead) -> io::Result<bool> {
+    let (mut header, mut read) = (String::new(), 0);
+    loop {
+        header.clear();
+        if !read_line(reader, &mut header, MAX_HEADERS - read)? {
+            return Ok(false);
+        }
+        read += header.len();
+        if header.len() <= 2 {
+            return Ok(true);
+        }
+    }
+}
+
+fn handle(stream: TcpStream, graph: &Graph) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    let too_long = if !read_line(&mut reader, &mut request, MAX_REQUEST_LINE)? {
+        Some(Response::error("414 URI Too Long", "request line too long"))
+    } else if !skip_headers(&mut reader)? {
+        Some(Response::error("431 Request Header Fields Too Large", "headers too long"))
+    } else {
+        None
+    };
+    if let Some(response) = too_long {
+        respond(&stream, &response)?;
+        // Closing with the rest of the request unread would reset the connection before the client
+        // reads why, so let it finish sending, up to a point
+        stream.shutdown(Shutdown::Write)?;
+        io::copy(&mut reader.take(MAX_HEADERS as u64), &mut io::sink())?;
+        return Ok(());
+    }
+
+    let response = match request.split_whitespace().collect::<Vec<_>>()[..] {
+        ["GET", target, _] => {
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            route(graph, path, &parameters(query))
+        }
+        [_, _, _] => Response::error("405 Method Not Allowed", "only GET is supported"),
+        _ => Response::error("400 Bad Request", "malformed request line"),
+    };
+    respond(&stream, &response)
+}
+
+fn respond(mut stream: &TcpStream, response: &Response) -> io::Result<()> {
+    let body = response.body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn route(graph: &Graph, path: &str, parameters: &HashMap<String, String>) -> Response {
+    let answer = match path {
+        "/health" => {
+            return Response::ok(Json::object([
+                ("status", "ok".into()),
+                ("stops", (graph.stop_count() as u32).into()),
+                ("trips", (graph.trips.len() as u32).into()),
+            ]))
+        }
+        "/route" | "/cost" | "/profile" | "/isochrone" => query(graph, path, parameters),
+        _ => return Response::error("404 Not Found", &format!("no such endpoint {}", path)),
+    };
+    match answer {
+        Ok(report) => Response::ok(report.to_json()),
+        Err(e) => Response::error("400 Bad Request", &e),
+    }
+}
+
+fn query(graph: &Graph, path: &str, parameters: &HashMap<String, String>) -> Result<Report, String> {
+    let stop = |name: &str| -> Result<Vertex, String> {
+        let value = parameters.get(name).ok_or_else(|| format!("missing {}", name))?;
+        value
+            .parse::<Vertex>()
+            .ok()
+            .filter(|&stop| (stop as usize) < graph.stop_count())
+            .ok_or_else(|| format!("there is no stop '{}'", value))
+    };
+    let at = match parameters.get("at") {
+        Some(at) => minutes(at).ok_or_else(|| format!("at '{}' is not minutes or H:MM", at))?,
+        None => 0,
+    };
+    // The other solvers answer the assignments, which always set off at 0, so an at would be ignored
+    let no_at = |name: &str| {
+        if parameters.contains_key("at") {
+            return Err(format!("{} doesn't take an at, only earliest, cost and isochrone do", name));
+        }
+        Ok(())
+    };
+    let exclusions = Exclusions::none();
+
+    match path {
+        "/route" => {
+            let (from, to) = (stop("from")?, stop("to")?);
+            let solver = parameters.get("solver").map_or("earliest", String::as_str);
+            if solvers::named::<Graph>(solver).is_none() {
+                return Err(format!("unknown solver '{}'", solver));
+            }
+            if solver != "earliest" {
+                no_at(solver)?;
+            }
+            Ok(Report::collect(|stats| Answer::route(graph, solver, from, to, at, &exclusions, stats).unwrap()))
+        }
+        "/cost" => {
+            let (from, to) = (stop("from")?, stop("to")?);
+            if parameters.contains_key("walk") {
+                return Err(NO_WALKING.to_string());
+            }
+            let mut weights = Generalized::default();
+            for (name, weight) in [("wait", &mut weights.wait), ("ride", &mut weights.ride), ("transfer", &mut weights.transfer)] {
+                if let Some(value) = parameters.get(name) {
+                    *weight = value.parse().map_err(|e| format!("{}: {}", name, e))?;
+                }
+            }
+            Ok(Report::collect(|stats| Answer::cost(graph, from, to, at, weights, &exclusions, stats)))
+        }
+        "/profile" => {
+            let (from, to) = (stop("from")?, stop("to")?);
+            no_at("profile")?;
+            Ok(Report::collect(|stats| Answer::Profile { from, to, journeys: profile::profile(graph, from, to, &exclusions, stats) }))
+        }
+        _ => {
+            let from = stop("from")?;
+            let within = parameters.get("within").ok_or("missing within")?;
+            let within = duration(within).ok_or_else(|| format!("within '{}' is not a duration like 30m", within))?;
+            Ok(Report::collect(|stats| Answer::Isochrone {
+                from,
+                at,
+                within,
+                reached: profile::isochrone(graph, from, at, within, &exclusions, stats),
+            }))
+        }
+    }
+}
+
+// "from=0&to=14" into its pairs, %XX and + decoded. A % without two hex digits after it stays as it is.
+fn parameters(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).filter(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+        let escaped = hex.map(|hex| hex.iter().fold(0, |byte, &digit| byte * 16 + (digit as char).to_digit(16).unwrap() as u8));
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,206 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::graph::Vertex;
+use crate::journey::{Journey, Leg};
+use crate::solvers::label::{CostModel, Label};
+use crate::time::TimeLike;
+
+// What the label setting search can optimise, see label.rs
+
+// Waiting since the label got to its stop, before riding `leg`
+fn waited<T: TimeLike>(label: &Label<T>, leg: &Leg<T>) -> T {
+    leg.departure.saturating_sub(label.time)
+}
+
+// Staying on the same trip isn't a transfer, neither is getting on the first bus
+fn transfers<T: TimeLike>(label: &Label<T>, leg: &Leg<T>) -> bool {
+    label.trip.is_some_and(|trip| trip != leg.trip)
+}
+
+// Earliest arrival. Getting somewhere earlier is never worse, so a stop only needs settling once.
+pub struct Earliest;
+
+impl<T: TimeLike> CostModel<T> for Earliest {
+    type Cost = T;
+    type State = Vertex;
+
+    fn start(&self, time: T) -> T {
+        time
+    }
+
+    fn extend(&self, _: T, _: &Label<T>, leg: &Leg<T>) -> T {
+        leg.arrival
+    }
+
+    fn state(&self, label: &Label<T>) -> Vertex {
+        label.stop
+    }
+}
+
+// Least waiting, the wait before the first bus included. Which trip we came on doesn't matter.
+pub struct MinWait;
+
+impl<T: TimeLike> CostModel<T> for MinWait {
+    type Cost = T;
+    type State = (Vertex, T);
+
+    fn start(&self, _: T) -> T {
+        T::ZERO
+    }
+
+    // Waiting never adds up to more than the time that went by, so this never saturates
+    fn extend(&self, cost: T, label: &Label<T>, leg: &Leg<T>) -> T {
+        cost.saturating_add(waited(label, leg))
+    }
+
+    fn state(&self, label: &Label<T>) -> (Vertex, T) {
+        (label.stop, label.time)
+    }
+}
+
+// Fewest transfers, and the earliest arrival of those
+pub struct MinTransfers;
+
+impl<T: TimeLike> CostModel<T> for MinTransfers {
+    type Cost = (usize, T);
+    type State = Label<T>;
+
+    fn start(&self, time: T) -> (usize, T) {
+        (0, time)
+    }
+
+    fn extend(&self, (count, _): (usize, T), label: &Label<T>, leg: &Leg<T>) -> (usize, T) {
+        (count + usize::from(transfers(label, leg)), leg.arrival)
+    }
+
+    fn state(&self, label: &Label<T>) -> Label<T> {
+        *label
+    }
+}
+
+/*
+ * A weight with two decimals, kept in hundredths so costs stay whole numbers and compare exactly: "1.5" is
+ * Fixed(150). Also what a generalized cost comes out as, in minutes.
+ */
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Fixed(pub u64);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+
+    pub fn whole(n: u64) -> Fixed {
+        Fixed(n.saturating_mul(100))
+    }
+
+    // This weight applied to `minutes`
+    pub fn times<T: Into<u64>>(self, minutes: T) -> Fixed {
+        Fixed(self.0.saturating_mul(minutes.into()))
+    }
+
+    pub fn saturating_add(self, other: Fixed) -> Fixed {
+        Fixed(self.0.saturating_add(other.0))
+    }
+}
+
+impl FromStr for Fixed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Fixed, String> {
+        let error = || format!("'{}' is not a number with at most two decimals", s);
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() || fraction.len() > 2 || !digits(whole) || !digits(fraction) {
+            return Err(error());
+        }
+        let whole = whole.parse::<u64>().map_err(|_| error())?;
+        let hundredths = format!("{:0<2}", fraction).parse::<u64>().map_err(|_| error())?;
+        whole.checked_mul(100).and_then(|n| n.checked_add(hundredths)).map(Fixed).ok_or_else(error)
+    }
+}
+
+// Without the decimals that are zero, so 2, 1.5 and 0.25
+impl Display for Fixed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0 % 100 {
+            0 => write!(f, "{}", self.0 / 100),
+            n if n % 10 == 0 => write!(f, "{}.{}", self.0 / 100, n / 10),
+            n => write!(f, "{}.{:02}", self.0 / 100, n),
+        }
+    }
+}
+
+/*
+ * What a journey feels like to a passenger: minutes waited and ridden, each times its weight, plus `transfer`
+ * minutes for every change of bus. A weight of 1 for waiting and riding and no transfer penalty is the time
+ * the journey takes, only waiting is MinWait. Saturates at the end of time, where every journey costs the same.
+ */
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Generalized {
+    pub wait: Fixed,
+    pub ride: Fixed,
+    pub transfer: Fixed,
+}
+
+// Asking for a walking weight gets this: our timetables have no footpaths, so there's no walking to weigh
+pub const NO_WALKING: &str = "there are no footpaths in the timetable, so there is no walking to weigh";
+
+// Waiting hurts twice as much as riding, and a change of bus is worth five minutes of it
+impl Default for Generalized {
+    fn default() -> Generalized {
+        Generalized { wait: Fixed::whole(2), ride: Fixed::whole(1), transfer: Fixed::whole(5) }
+    }
+}
+
+// Where the generalized cost of a journey comes from
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Breakdown<T = u32> {
+    pub wait: T,
+    pub ride: T,
+    pub transfers: usize,
+    pub wait_cost: Fixed,
+    pub ride_cost: Fixed,
+    pub transfer_cost: Fixed,
+}
+
+impl<T> Breakdown<T> {
+    pub fn cost(&self) -> Fixed {
+        self.wait_cost.saturating_add(self.ride_cost).saturating_add(self.transfer_cost)
+    }
+}
+
+impl Generalized {
+    // The costs add up leg by leg, so this is what the search found the journey to cost
+    pub fn breakdown<T: TimeLike + Into<u64>>(&self, journey: &Journey<T>) -> Breakdown<T> {
+        let (wait, ride, transfers) = (journey.wait_time(), journey.ride_time(), journey.transfers());
+        Breakdown {
+            wait,
+            ride,
+            transfers,
+            wait_cost: self.wait.times(wait),
+            ride_cost: self.ride.times(ride),
+            transfer_cost: self.transfer.times(transfers as u64),
+        }
+    }
+}
+
+impl<T: TimeLike + Into<u64>> CostModel<T> for Generalized {
+    type Cost = Fixed;
+    type State = Label<T>;
+
+    fn start(&self, _: T) -> Fixed {
+        Fixed::ZERO
+    }
+
+    fn extend(&self, cost: Fixed, label: &Label<T>, leg: &Leg<T>) -> Fixed {
+        let ride = leg.arrival.saturating_sub(leg.departure);
+        let transfer = if transfers(label, leg) { self.transfer } else { Fixed::ZERO };
+        cost.saturating_add(self.wait.times(waited(label, leg)))
+            .saturating_add(self.ride.times(ride))
+            .saturating_add(transfer)
+    }
+
+    fn state(&self, label: &Label<T>) -> Label<T> {
+        *label
+    }
+}
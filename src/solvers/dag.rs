@@ -0,0 +1,144 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+
+use log::{debug, trace};
+
+use crate::exclusions::Exclusions;
+use crate::graph::{Timetable, Vertex};
+use crate::journey::{Journey, Leg};
+use crate::solvers::stats::SearchStats;
+use crate::time::TimeLike;
+
+/*
+ * Most total waiting again, the way main.rs went about it: a shortest path over negative weights in a DAG,
+ * which is the longest path over the minutes waited. Done as the longest, so waits never have to fit an i64.
+ * The DAG is the time-expanded graph, with a node for every time a bus leaves or arrives somewhere.
+ * Riding and waiting only go forward in time, except for buses that take no time at all between stops.
+ * Those can go round in circles within a minute, so it's only a DAG once every minute is taken as a whole.
+ */
+
+struct Arc<T> {
+    to: usize,
+    weight: T,           // Minutes waited
+    leg: Option<Leg<T>>, // None when staying put
+}
+
+struct TimeExpanded<T> {
+    events: Vec<(Vertex, T)>, // Sorted, the index is the node
+    adjacency: Vec<Vec<Arc<T>>>,
+}
+
+impl<T: TimeLike> TimeExpanded<T> {
+    fn new<G: Timetable<Time = T>>(graph: &G, start: Vertex, end: Vertex, exclusions: &Exclusions) -> TimeExpanded<T> {
+        let mut legs = vec![];
+        let mut events = BTreeSet::from([(start, T::ZERO)]);
+        for stop in 0..graph.stop_count() as Vertex {
+            // Once home we stay home
+            if stop == end {
+                continue;
+            }
+            for edge in graph.every_departure(stop, T::ZERO, exclusions) {
+                let leg = Leg::new(stop, &edge);
+                events.insert((leg.from, leg.departure));
+                events.insert((leg.to, leg.arrival));
+                legs.push(leg);
+            }
+        }
+
+        let events = events.into_iter().collect::<Vec<_>>();
+        let node: HashMap<(Vertex, T), usize> = events.iter().enumerate().map(|(i, &event)| (event, i)).collect();
+        let mut graph = TimeExpanded {
+            adjacency: (0..events.len()).map(|_| vec![]).collect(),
+            events,
+        };
+
+        for leg in legs {
+            graph.add_arc(node[&(leg.from, leg.departure)], node[&(leg.to, leg.arrival)], T::ZERO, Some(leg));
+        }
+        for i in 1..graph.events.len() {
+            let ((previous_stop, previous_time), (stop, time)) = (graph.events[i - 1], graph.events[i]);
+            if stop == previous_stop && stop != end {
+                graph.add_arc(i - 1, i, time.saturating_sub(previous_time), None);
+            }
+        }
+        graph
+    }
+
+    fn add_arc(&mut self, from: usize, to: usize, weight: T, leg: Option<Leg<T>>) {
+        self.adjacency[from].push(Arc { to, weight, leg });
+    }
+
+    // The nodes grouped by minute, earliest first. Arcs only go from one group to a later one or
+    // stay within the same group, which makes this the topological order of the minutes.
+    fn minutes(&self) -> Vec<Vec<usize>> {
+        let mut order = (0..self.events.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&node| (self.events[node].1, self.events[node].0));
+        order.chunk_by(|&a, &b| self.events[a].1 == self.events[b].1).map(<[usize]>::to_vec).collect()
+    }
+}
+
+pub fn dag_shortest_path<G: Timetable>(
+    graph: &G,
+    start: Vertex,
+    end: Vertex,
+    exclusions: &Exclusions,
+    stats: &mut SearchStats,
+) -> Option<Journey<G::Time>> {
+    stats.timed(|stats| search(graph, start, end, exclusions, stats))
+}
+
+fn search<G: Timetable>(graph: &G, start: Vertex, end: Vertex, exclusions: &Exclusions, stats: &mut SearchStats) -> Option<Journey<G::Time>> {
+    let dag = TimeExpanded::new(graph, start, end, exclusions);
+    let origin = dag.events.binary_search(&(start, G::Time::ZERO)).unwrap();
+
+    // Most waited getting to each node, None until reached
+    let mut waited: Vec<Option<G::Time>> = vec![None; dag.events.len()];
+    let mut prev = vec![None; dag.events.len()];
+    waited[origin] = Some(G::Time::ZERO);
+
+    // Relax edges a minute at a time, within a minute until nothing improves. Arcs within a minute
+    // are rides taking no time and weigh nothing, so going round in circles never improves anything.
+    for minute in dag.minutes() {
+        let mut pending = minute.into_iter().filter(|&node| waited[node].is_some()).collect::<Vec<_>>();
+        stats.heap(pending.len());
+        while let Some(node) = pending.pop() {
+            stats.settled += 1;
+            let so_far = waited[node].unwrap();
+            trace!(stop = dag.events[node].0, time = dag.events[node].1, waited = so_far; "pop");
+            for arc in &dag.adjacency[node] {
+                // A path only ever goes forward in time, so its waits never sum to more than its last event time
+                let new_waited = so_far.saturating_add(arc.weight);
+                let improved = waited[arc.to].is_none_or(|waited| new_waited > waited);
+                trace!(to = dag.events[arc.to].0, time = dag.events[arc.to].1, waited = new_waited, improved; "relax");
+                stats.relaxed += 1;
+                if !improved {
+                    stats.dominated += 1;
+                } else {
+                    waited[arc.to] = Some(new_waited);
+                    prev[arc.to] = Some((node, arc.leg));
+                    if dag.events[arc.to].1 == dag.events[node].1 {
+                        pending.push(arc.to);
+                        stats.heap(pending.len());
+                    }
+                }
+            }
+        }
+    }
+
+    let arcs = dag.adjacency.iter().map(Vec::len).sum::<usize>();
+    debug!(start, end, events = dag.events.len(), arcs; "time-expanded graph relaxed");
+
+    // The best of all the times we could be home, the earliest of those that wait the most
+    let home = (0..dag.events.len())
+        .filter(|&node| dag.events[node].0 == end && waited[node].is_some())
+        .min_by_key(|&node| Reverse(waited[node]))?;
+
+    let mut legs = vec![];
+    let mut node = home;
+    while let Some((previous, leg)) = prev[node] {
+        legs.extend(leg);
+        node = previous;
+    }
+    legs.reverse();
+    Some(Journey { origin: start, start: G::Time::ZERO, legs })
+}
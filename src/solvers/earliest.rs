@@ -0,0 +1,141 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+use log::{debug, trace};
+
+use crate::calendar::DateTime;
+use crate::exclusions::Exclusions;
+use crate::graph::{Timetable, Vertex};
+use crate::journey::{Journey, Leg};
+use crate::solvers::cost::Earliest;
+use crate::solvers::label;
+use crate::solvers::stats::SearchStats;
+use crate::time::TimeLike;
+
+// Earliest arrival, AssignmentOne
+
+// Ignores calendars: every bus line runs, starting at time 0 like the assignment
+pub fn dijkstra<G: Timetable>(graph: &G, start: Vertex, end: Vertex, exclusions: &Exclusions, stats: &mut SearchStats) -> Option<Journey<G::Time>> {
+    dijkstra_from(graph, start, end, G::Time::ZERO, exclusions, stats)
+}
+
+// Same, but setting off at `time`
+pub fn dijkstra_from<G: Timetable>(
+    graph: &G,
+    start: Vertex,
+    end: Vertex,
+    time: G::Time,
+    exclusions: &Exclusions,
+    stats: &mut SearchStats,
+) -> Option<Journey<G::Time>> {
+    label::search(graph, start, end, time, exclusions, &Earliest, stats).map(|(journey, _)| journey)
+}
+
+// Earliest arrival at every stop when setting off from `start` at `time`, None where we never get
+pub fn arrivals<G: Timetable>(
+    graph: &G,
+    start: Vertex,
+    time: G::Time,
+    exclusions: &Exclusions,
+    stats: &mut SearchStats,
+) -> Vec<Option<G::Time>> {
+    label::cheapest(graph, start, time, exclusions, &Earliest, stats)
+}
+
+// Times are minutes after midnight of the query date, so they go negative for
+// runs of the previous service day and past a day for the following ones.
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct DatedState {
+    time: i64,
+    position: u32,
+}
+
+impl PartialOrd for DatedState {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DatedState {
+    fn cmp(&self, other: &DatedState) -> Ordering {
+        other.time.cmp(&self.time)
+            .then_with(|| self.position.cmp(&other.position))
+    }
+}
+
+// Only takes trips whose calendar runs on their service day, waiting for later days when needed. The legs
+// of the journey are dated, each with the day it actually runs on.
+pub fn dijkstra_on<G: Timetable<Time = u32>>(
+    graph: &G,
+    start: Vertex,
+    end: Vertex,
+    departure: DateTime,
+    exclusions: &Exclusions,
+    stats: &mut SearchStats,
+) -> Option<Journey<DateTime>> {
+    stats.timed(|stats| dated_search(graph, start, end, departure, exclusions, stats))
+}
+
+fn dated_search<G: Timetable<Time = u32>>(
+    graph: &G,
+    start: Vertex,
+    end: Vertex,
+    departure: DateTime,
+    exclusions: &Exclusions,
+    stats: &mut SearchStats,
+) -> Option<Journey<DateTime>> {
+    let base = departure.date;
+    let mut dist: Vec<i64> = vec![i64::MAX; graph.stop_count()];
+    // The leg that got to each stop soonest so far
+    let mut previous: Vec<Option<Leg<DateTime>>> = vec![None; graph.stop_count()];
+    let mut heap: BinaryHeap<DatedState> = BinaryHeap::new();
+    let mut visited: HashSet<Vertex> = HashSet::new();
+
+    dist[start as usize] = departure.time as i64;
+    heap.push(DatedState { time: departure.time as i64, position: start });
+
+    while let Some(DatedState { time: current_time, position }) = heap.pop() {
+        trace!(stop = position, time = current_time; "pop");
+        if !visited.insert(position) {
+            stats.dominated += 1;
+            continue;
+        }
+        stats.settled += 1;
+
+        if position == end {
+            debug!(start, end, settled = visited.len(); "dated earliest arrival search done");
+            let mut legs = vec![];
+            let mut stop = end;
+            while let Some(leg) = previous[stop as usize] {
+                legs.push(leg);
+                stop = leg.from;
+            }
+            legs.reverse();
+            return Some(Journey { origin: start, start: departure, legs });
+        }
+
+        for (leaves, edge) in graph.dated_departures(position, base, current_time, exclusions) {
+            let next = DatedState { time: leaves + edge.weight as i64, position: edge.to };
+            let improved = next.time < dist[next.position as usize];
+            trace!(from = position, to = next.position, departure = leaves, arrival = next.time, improved; "relax");
+            stats.relaxed += 1;
+            if improved {
+                trace!(stop = next.position, time = next.time; "push");
+                heap.push(next);
+                stats.heap(heap.len());
+                dist[next.position as usize] = next.time;
+                previous[next.position as usize] = Some(Leg {
+                    from: position,
+                    to: next.position,
+                    departure: DateTime::from_offset(base, leaves),
+                    arrival: DateTime::from_offset(base, next.time),
+                    trip: edge.trip,
+                });
+            } else {
+                stats.dominated += 1;
+            }
+        }
+    }
+    debug!(start, end, settled = visited.len(); "dated earliest arrival search found nothing");
+    None
+}
@@ -0,0 +1,148 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use log::{debug, trace};
+
+use crate::exclusions::Exclusions;
+use crate::graph::{Timetable, TripId, Vertex};
+use crate::journey::{Journey, Leg};
+use crate::solvers::stats::SearchStats;
+use crate::time::TimeLike;
+
+/*
+ * One time-dependent Dijkstra for any objective. A label is where a journey got to: the stop, when it got
+ * there and the trip it came on. The cost model says what taking one more leg from a label costs and what
+ * of a label the rest of the journey depends on. Labels are settled cheapest first, and once a label with
+ * some state is settled every other label with the same state is dropped, so the first label home is the
+ * cheapest journey there. A label is only pushed at all if it's cheaper than every label made before it
+ * with the same state, which keeps the heap about as small as a plain Dijkstra's.
+ *
+ * That is exact as long as a leg never makes a journey cheaper, and two labels with the same state get
+ * charged the same from there on. Only the next run of a frequency line is offered, so waiting for a
+ * later run has to cost the same as riding the next one and waiting at the stop after.
+ */
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Label<T = u32> {
+    pub stop: Vertex,
+    pub time: T,
+    pub trip: Option<TripId>, // None before the first leg
+}
+
+pub trait CostModel<T: TimeLike> {
+    // Cheapest first
+    type Cost: Copy + Ord + Debug;
+    // What of a label the cost of going on from it depends on
+    type State: Copy + Eq + Hash;
+
+    fn start(&self, time: T) -> Self::Cost;
+
+    // The cost of riding `leg` from `label`, never less than `cost`
+    fn extend(&self, cost: Self::Cost, label: &Label<T>, leg: &Leg<T>) -> Self::Cost;
+
+    fn state(&self, label: &Label<T>) -> Self::State;
+}
+
+// The cheapest journey from `start` to `end` setting off at `time`, with what it cost
+pub fn search<G: Timetable, M: CostModel<G::Time>>(
+    graph: &G,
+    start: Vertex,
+    end: Vertex,
+    time: G::Time,
+    exclusions: &Exclusions,
+    model: &M,
+    stats: &mut SearchStats,
+) -> Option<(Journey<G::Time>, M::Cost)> {
+    let settled = stats.timed(|stats| settle(graph, start, Some(end), time, exclusions, model, stats));
+    let (mut index, cost) = settled.home?;
+    let mut legs = vec![];
+    while let Some((previous, leg)) = settled.labels[index].1 {
+        legs.push(leg);
+        index = previous;
+    }
+    legs.reverse();
+    Some((Journey { origin: start, start: time, legs }, cost))
+}
+
+// What the cheapest journey from `start` to every stop costs, None where we never get
+pub fn cheapest<G: Timetable, M: CostModel<G::Time>>(
+    graph: &G,
+    start: Vertex,
+    time: G::Time,
+    exclusions: &Exclusions,
+    model: &M,
+    stats: &mut SearchStats,
+) -> Vec<Option<M::Cost>> {
+    stats.timed(|stats| settle(graph, start, None, time, exclusions, model, stats)).reached
+}
+
+// A label with the index of the one it came from and the leg that got it there, None for the start
+type Made<T> = (Label<T>, Option<(usize, Leg<T>)>);
+
+struct Settled<T, C> {
+    reached: Vec<Option<C>>, // Cost of the first label settled at each stop, the cheapest there
+    labels: Vec<Made<T>>,    // Every label made
+    home: Option<(usize, C)>, // The first label that got to `end`
+}
+
+// Settles labels until one gets to `end`, or until there are none left when there is no end
+fn settle<G: Timetable, M: CostModel<G::Time>>(
+    graph: &G,
+    start: Vertex,
+    end: Option<Vertex>,
+    time: G::Time,
+    exclusions: &Exclusions,
+    model: &M,
+    stats: &mut SearchStats,
+) -> Settled<G::Time, M::Cost> {
+    let mut labels = vec![(Label { stop: start, time, trip: None }, None)];
+    let mut reached = vec![None; graph.stop_count()];
+    let mut settled: HashSet<M::State> = HashSet::new();
+    let mut best: HashMap<M::State, M::Cost> = HashMap::from([(model.state(&labels[0].0), model.start(time))]);
+    // Ties go to the label made first, so they go the same way every time
+    let mut heap = BinaryHeap::from([Reverse((model.start(time), 0))]);
+
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        let label = labels[index].0;
+        trace!(stop = label.stop, time = label.time, cost:? = cost; "pop");
+        reached[label.stop as usize].get_or_insert(cost);
+        if Some(label.stop) == end {
+            debug!(start, end = label.stop, cost:? = cost, labels = labels.len(), settled = settled.len(); "label setting search done");
+            return Settled { reached, labels, home: Some((index, cost)) };
+        }
+        if !settled.insert(model.state(&label)) {
+            trace!(stop = label.stop, time = label.time, cost:? = cost; "prune");
+            stats.dominated += 1;
+            continue;
+        }
+        stats.settled += 1;
+
+        for edge in graph.departures(label.stop, label.time, exclusions) {
+            let leg = Leg::new(label.stop, &edge);
+            let next = Label { stop: leg.to, time: leg.arrival, trip: Some(leg.trip) };
+            stats.relaxed += 1;
+            let state = model.state(&next);
+            if settled.contains(&state) {
+                trace!(from = label.stop, to = next.stop, arrival = leg.arrival; "prune");
+                stats.dominated += 1;
+                continue;
+            }
+            let next_cost = model.extend(cost, &label, &leg);
+            trace!(from = label.stop, to = next.stop, departure = leg.departure, arrival = leg.arrival, cost:? = next_cost; "relax");
+            if best.get(&state).is_some_and(|&known| known <= next_cost) {
+                trace!(from = label.stop, to = next.stop, arrival = leg.arrival, cost:? = next_cost; "prune");
+                stats.dominated += 1;
+                continue;
+            }
+            best.insert(state, next_cost);
+            labels.push((next, Some((index, leg))));
+            heap.push(Reverse((next_cost, labels.len() - 1)));
+            trace!(stop = next.stop, time = next.time, cost:? = next_cost; "push");
+            stats.heap(heap.len());
+        }
+    }
+    debug!(start, labels = labels.len(), settled = settled.len(), reached = reached.iter().flatten().count(); "label setting search done without getting to an end");
+    Settled { reached, labels, home: None }
+}
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, VecDeque};
+
+use log::{debug, trace};
+
+use crate::exclusions::Exclusions;
+use crate::graph::{Timetable, Vertex};
+use crate::journey::{Journey, Leg};
+use crate::solvers::stats::SearchStats;
+use crate::time::TimeLike;
+
+// Most total waiting on the way home, AssignmentTwo
+
+// (location, time) -> most waiting still possible from there, with the leg that gets it.
+// None when home can't be reached from there at all.
+pub type Cache<T = u32> = HashMap<(Vertex, T), Option<(T, Leg<T>)>>;
+
+pub fn dfs<G: Timetable>(graph: &G, start: Vertex, end: Vertex, exclusions: &Exclusions, stats: &mut SearchStats) -> Option<Journey<G::Time>> {
+    let zero = G::Time::ZERO;
+    let mut cache = Cache::new();
+    let most = stats.timed(|stats| visit(graph, start, end, zero, exclusions, &mut cache, stats));
+    debug!(start, end, waited = most, cached = cache.len(); "most waiting search done");
+    most?;
+
+    // Follow the best leg out of every state from the start. Stops passed within a minute on legs that take
+    // no time may not have been looked at on their own yet.
+    let mut legs = vec![];
+    let mut state = (start, zero);
+    while state.0 != end {
+        if !cache.contains_key(&state) {
+            visit(graph, state.0, end, state.1, exclusions, &mut cache, stats);
+        }
+        let Some(Some((_, leg))) = cache.get(&state) else {
+            break;
+        };
+        legs.push(*leg);
+        state = (leg.to, leg.arrival);
+    }
+    Some(Journey { origin: start, start: zero, legs })
+}
+
+/*
+ * Time only moves forward, except on legs that take no time at all, which could go round in circles. Going
+ * round never adds any waiting, so the most waiting from here is the most from any stop those legs reach
+ * within this minute, by a leg that does move time forward. Only those get searched, so every state the
+ * search gets to is later than this one and its answer is the same whichever way we got there.
+ */
+fn visit<G: Timetable>(
+    graph: &G,
+    location: Vertex,
+    end: Vertex,
+    time: G::Time,
+    exclusions: &Exclusions,
+    cache: &mut Cache<G::Time>,
+    stats: &mut SearchStats,
+) -> Option<G::Time> {
+    if location == end {
+        return Some(G::Time::ZERO);
+    }
+
+    if let Some(&cached_result) = cache.get(&(location, time)) {
+        trace!(stop = location, time; "cache hit");
+        stats.cache_hits += 1;
+        return cached_result.map(|(transfer_time, _)| transfer_time);
+    }
+
+    trace!(stop = location, time; "cache miss");
+    stats.cache_misses += 1;
+
+    // Ties go to the lowest stop and then the first leg out of it, so whichever stop of this minute we start
+    // from, the best leg of every one of them leads on towards the same one and following them ends
+    let mut best: Option<(G::Time, Leg<G::Time>)> = None;
+    for (stop, first) in same_minute(graph, location, end, time, exclusions) {
+        if stop == end {
+            if best.is_none() {
+                best = first.map(|leg| (G::Time::ZERO, leg));
+            }
+            continue;
+        }
+        for edge in graph.every_departure(stop, time, exclusions) {
+            if edge.departure_time == time && edge.weight == G::Time::ZERO {
+                continue;
+            }
+            let arrival = edge.arrival();
+            let wait_time = edge.departure_time.saturating_sub(time);
+            trace!(from = stop, to = edge.to, departure = edge.departure_time, arrival, waited = wait_time; "relax");
+            stats.relaxed += 1;
+            if let Some(rest) = visit(graph, edge.to, end, arrival, exclusions, cache, stats) {
+                let waited = wait_time.saturating_add(rest);
+                if best.is_none_or(|(max_transfer_time, _)| waited > max_transfer_time) {
+                    best = Some((waited, first.unwrap_or_else(|| Leg::new(stop, &edge))));
+                }
+            }
+        }
+    }
+
+    stats.settled += 1;
+    trace!(stop = location, time, most = best.map(|(transfer_time, _)| transfer_time); "cache store");
+    cache.insert((location, time), best);
+    best.map(|(transfer_time, _)| transfer_time)
+}
+
+// Stops reached from `location` at `time` on legs that take no time, lowest first, with the first leg of
+// a fewest legs way there, None for `location` itself. Home is where we get off.
+fn same_minute<G: Timetable>(
+    graph: &G,
+    location: Vertex,
+    end: Vertex,
+    time: G::Time,
+    exclusions: &Exclusions,
+) -> Vec<(Vertex, Option<Leg<G::Time>>)> {
+    let mut first = HashMap::from([(location, None)]);
+    let mut queue = VecDeque::from([location]);
+    while let Some(stop) = queue.pop_front() {
+        if stop == end {
+            continue;
+        }
+        let way = first[&stop];
+        let now = graph.every_departure(stop, time, exclusions).filter(|edge| edge.departure_time == time && edge.weight == G::Time::ZERO);
+        for edge in now {
+            first.entry(edge.to).or_insert_with(|| {
+                queue.push_back(edge.to);
+                Some(way.unwrap_or_else(|| Leg::new(stop, &edge)))
+            });
+        }
+    }
+    let mut stops = first.into_iter().collect::<Vec<_>>();
+    stops.sort_unstable_by_key(|&(stop, _)| stop);
+    stops
+}
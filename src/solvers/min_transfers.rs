@@ -0,0 +1,12 @@
+use crate::exclusions::Exclusions;
+use crate::graph::{Timetable, Vertex};
+use crate::journey::Journey;
+use crate::solvers::cost::MinTransfers;
+use crate::solvers::label;
+use crate::solvers::stats::SearchStats;
+use crate::time::TimeLike;
+
+// Fewest changes of bus, getting there earliest of those
+pub fn dijkstra<G: Timetable>(graph: &G, start: Vertex, end: Vertex, exclusions: &Exclusions, stats: &mut SearchStats) -> Option<Journey<G::Time>> {
+    label::search(graph, start, end, G::Time::ZERO, exclusions, &MinTransfers, stats).map(|(journey, _)| journey)
+}
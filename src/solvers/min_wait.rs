@@ -0,0 +1,13 @@
+use crate::exclusions::Exclusions;
+use crate::graph::{Timetable, Vertex};
+use crate::journey::Journey;
+use crate::solvers::cost::MinWait;
+use crate::solvers::label;
+use crate::solvers::stats::SearchStats;
+use crate::time::TimeLike;
+
+// Least total waiting, AssigmentThree
+
+pub fn dijkstra<G: Timetable>(graph: &G, start: Vertex, end: Vertex, exclusions: &Exclusions, stats: &mut SearchStats) -> Option<Journey<G::Time>> {
+    label::search(graph, start, end, G::Time::ZERO, exclusions, &MinWait, stats).map(|(journey, _)| journey)
+}
@@ -0,0 +1,33 @@
+use crate::exclusions::Exclusions;
+use crate::graph::{Graph, Timetable, Vertex};
+use crate::journey::Journey;
+use crate::solvers::stats::SearchStats;
+
+pub mod cost;
+pub mod dag;
+pub mod earliest;
+pub mod label;
+pub mod max_wait;
+pub mod min_transfers;
+pub mod min_wait;
+pub mod profile;
+pub mod stats;
+
+// What every solver looks like: the best journey from start to end by its own objective, counting
+// the work it did in the stats
+pub type Solver<G = Graph> = fn(&G, Vertex, Vertex, &Exclusions, &mut SearchStats) -> Option<Journey<<G as Timetable>::Time>>;
+
+// The value a solver optimises, read off the journey it found
+pub type Objective<T = u32> = fn(&Journey<T>) -> T;
+
+// The solvers by the name the command line knows them by, each with the value it optimises
+pub fn named<G: Timetable>(name: &str) -> Option<(Solver<G>, Objective<G::Time>)> {
+    match name {
+        "earliest" => Some((earliest::dijkstra, Journey::arrival)),
+        "min-wait" => Some((min_wait::dijkstra, Journey::wait_time)),
+        "min-transfers" => Some((min_transfers::dijkstra, Journey::transfer_count)),
+        "max-wait" => Some((max_wait::dfs, Journey::wait_time)),
+        "dag-longest" => Some((dag::dag_shortest_path, Journey::wait_time)),
+        _ => None,
+    }
+}
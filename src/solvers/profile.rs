@@ -0,0 +1,73 @@
+use crate::exclusions::Exclusions;
+use crate::graph::{Timetable, Vertex};
+use crate::journey::Journey;
+use crate::solvers::earliest;
+use crate::solvers::stats::SearchStats;
+use crate::time::TimeLike;
+
+// Questions answered with a bunch of earliest arrival searches, the stats adding them all up
+
+/*
+ * Every departure from `start` worth taking to `end` over the whole day: going through the
+ * departures latest first, a journey is kept only when it gets there before anything leaving
+ * later does. Each journey starts at its first departure, earliest journey first.
+ */
+pub fn profile<G: Timetable>(graph: &G, start: Vertex, end: Vertex, exclusions: &Exclusions, stats: &mut SearchStats) -> Vec<Journey<G::Time>> {
+    if start == end {
+        return vec![];
+    }
+    let mut times = graph.every_departure(start, G::Time::ZERO, exclusions).map(|edge| edge.departure_time).collect::<Vec<_>>();
+    times.sort_unstable();
+    times.dedup();
+
+    let mut journeys: Vec<Journey<G::Time>> = vec![];
+    let mut best = None;
+    for &time in times.iter().rev() {
+        if let Some(mut journey) = earliest::dijkstra_from(graph, start, end, time, exclusions, stats) {
+            if best.is_none_or(|best| journey.arrival() < best) {
+                best = Some(journey.arrival());
+                journey.start = journey.legs[0].departure;
+                journeys.push(journey);
+            }
+        }
+    }
+    journeys.reverse();
+    journeys
+}
+
+// Stops reached from `start` within `within` minutes of setting off at `time`, soonest first
+pub fn isochrone<G: Timetable>(
+    graph: &G,
+    start: Vertex,
+    time: G::Time,
+    within: G::Time,
+    exclusions: &Exclusions,
+    stats: &mut SearchStats,
+) -> Vec<(Vertex, G::Time)> {
+    let limit = time.saturating_add(within);
+    let mut reached = earliest::arrivals(graph, start, time, exclusions, stats)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(stop, arrival)| arrival.filter(|&arrival| arrival <= limit).map(|arrival| (stop as Vertex, arrival)))
+        .collect::<Vec<_>>();
+    reached.sort_by_key(|&(stop, arrival)| (arrival, stop));
+    reached
+}
+
+// Earliest arrival from each origin at each destination, setting off at `time`
+pub fn matrix<G: Timetable>(
+    graph: &G,
+    origins: &[Vertex],
+    destinations: &[Vertex],
+    time: G::Time,
+    exclusions: &Exclusions,
+    stats: &mut SearchStats,
+) -> Vec<Vec<Option<G::Time>>> {
+    origins
+        .iter()
+        .map(|&origin| {
+            let arrivals = earliest::arrivals(graph, origin, time, exclusions, stats);
+            destinations.iter().map(|&destination| arrivals[destination as usize]).collect()
+        })
+        .collect()
+}
@@ -0,0 +1,53 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use crate::json::Json;
+
+// What a search went through to get its answer, added up over every search it's handed to
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SearchStats {
+    pub settled: usize,      // Stops, or (stop, time) states, that were done with
+    pub relaxed: usize,      // Departures looked at
+    pub heap_max: usize,     // Most entries waiting in the priority queue or worklist at once
+    pub dominated: usize,    // Labels thrown away because something at least as good was already known
+    pub cache_hits: usize,   // For the memoised dfs of max_wait
+    pub cache_misses: usize,
+    pub elapsed: Duration,
+}
+
+impl SearchStats {
+    // Runs a search, adding its wall time
+    pub fn timed<T>(&mut self, search: impl FnOnce(&mut SearchStats) -> T) -> T {
+        let started = Instant::now();
+        let result = search(self);
+        self.elapsed += started.elapsed();
+        result
+    }
+
+    pub fn heap(&mut self, size: usize) {
+        self.heap_max = self.heap_max.max(size);
+    }
+
+    pub fn to_json(&self) -> Json {
+        let count = |n: usize| Json::Number(n as f64);
+        Json::object([
+            ("settled", count(self.settled)),
+            ("relaxed", count(self.relaxed)),
+            ("heap_max", count(self.heap_max)),
+            ("dominated", count(self.dominated)),
+            ("cache_hits", count(self.cache_hits)),
+            ("cache_misses", count(self.cache_misses)),
+            ("elapsed_us", Json::Number(self.elapsed.as_micros() as f64)),
+        ])
+    }
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "settled {}, relaxed {}, heap max {}, dominated {}, cache {} hits {} misses, {:.1?}",
+            self.settled, self.relaxed, self.heap_max, self.dominated, self.cache_hits, self.cache_misses, self.elapsed
+        )
+    }
+}
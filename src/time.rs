@@ -0,0 +1,77 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use log::kv::ToValue;
+
+/*
+ * What the timetable and the solvers need of a time: minutes in a u32 like the assignment, or a u64 for
+ * seconds or feeds counting from further back. Nothing adds times with a plain `+`, so nothing wraps around
+ * in a release build. Sums that could get past the end of time are checked, and the loaders report those
+ * as errors. Saturating is for sums that can't get there on a graph that loaded, like the waits of a journey.
+ */
+pub trait TimeLike: Copy + Ord + Hash + Default + Debug + Display + FromStr + ToValue + From<u16> + Send + Sync + 'static {
+    const ZERO: Self;
+    const MAX: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn saturating_add(self, other: Self) -> Self;
+    fn saturating_sub(self, other: Self) -> Self;
+    fn saturating_mul(self, other: Self) -> Self;
+    fn div_floor(self, other: Self) -> Self;
+    fn div_ceil(self, other: Self) -> Self;
+}
+
+macro_rules! time_like {
+    ($($t:ty),*) => {$(
+        // Inlined, or every leg relaxed in another crate would pay for a call
+        impl TimeLike for $t {
+            const ZERO: Self = 0;
+            const MAX: Self = <$t>::MAX;
+
+            #[inline]
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            #[inline]
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            #[inline]
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            #[inline]
+            fn saturating_add(self, other: Self) -> Self {
+                <$t>::saturating_add(self, other)
+            }
+
+            #[inline]
+            fn saturating_sub(self, other: Self) -> Self {
+                <$t>::saturating_sub(self, other)
+            }
+
+            #[inline]
+            fn saturating_mul(self, other: Self) -> Self {
+                <$t>::saturating_mul(self, other)
+            }
+
+            #[inline]
+            fn div_floor(self, other: Self) -> Self {
+                self / other
+            }
+
+            #[inline]
+            fn div_ceil(self, other: Self) -> Self {
+                <$t>::div_ceil(self, other)
+            }
+        }
+    )*};
+}
+
+time_like!(u16, u32, u64);
@@ -0,0 +1,143 @@
+use pathfinding::audit::{audit, replay};
+use pathfinding::exclusions::Exclusions;
+use pathfinding::format::Format;
+use pathfinding::generate::Generator;
+use pathfinding::graph::{Graph, Timetable};
+use pathfinding::journey::Journey;
+use pathfinding::json::Json;
+use pathfinding::report::{Answer, Report};
+use pathfinding::solvers::cost::{Fixed, Generalized};
+use pathfinding::solvers::stats::SearchStats;
+
+/*
+ * Answers the solvers give hold up when replayed, and answers tampered with in any of the ways a hand
+ * edited or buggy one could be get told apart. The timetable is tests/fixtures/golden/same_minute_transfer.txt,
+ * where the bus to 2 leaves 1 the minute the bus from 0 gets there.
+ */
+
+const TIMETABLE: &str = "3 3\n0 1\n10 20\n1 2\n20 30\n0 2\n12 45\n";
+
+fn report(graph: &Graph, solver: &str) -> Report {
+    Report::collect(|stats| Answer::route(graph, solver, 0, 2, 0, &Exclusions::none(), stats).unwrap())
+}
+
+// The answer with the journey and the value swapped for others
+fn answer(solver: &str, journey: Journey, value: u32) -> Json {
+    let answer = Answer::Route { solver: solver.to_string(), from: 0, to: 2, value: Some(value), journey: Some(journey) };
+    Report { answer, stats: SearchStats::default() }.to_json()
+}
+
+fn earliest(graph: &Graph) -> Journey {
+    match report(graph, "earliest").answer {
+        Answer::Route { journey: Some(journey), .. } => journey,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn solver_answers_hold_up() {
+    let generated = Generator { lines: 8, stops: 15, seed: 7, ..Generator::default() }.generate();
+    for graph in [Graph::parse(TIMETABLE, Format::Text).unwrap(), generated.graph()] {
+        let home = graph.stop_count() as u32 - 1;
+        for solver in ["earliest", "min-wait", "max-wait", "dag-longest"] {
+            let report = Report::collect(|stats| Answer::route(&graph, solver, 0, home, 0, &Exclusions::none(), stats).unwrap());
+            let json = Json::parse(&report.to_json().to_string()).unwrap();
+            assert_eq!(audit(&graph, &json, 0).unwrap(), Vec::<String>::new(), "{}", solver);
+        }
+    }
+}
+
+#[test]
+fn made_up_legs_are_caught() {
+    let graph = Graph::parse(TIMETABLE, Format::Text).unwrap();
+    let mut journey = earliest(&graph);
+    journey.legs[0].departure += 1;
+    journey.legs[0].arrival += 1;
+    let problems = audit(&graph, &answer("earliest", journey, 31), 0).unwrap();
+    assert_eq!(problems, ["leg 1 from 0 to 1 leaving 11 arriving 21 on trip 0 isn't in the timetable"]);
+}
+
+#[test]
+fn changing_buses_takes_the_buffer() {
+    let graph = Graph::parse(TIMETABLE, Format::Text).unwrap();
+    let journey = earliest(&graph);
+    assert_eq!(replay(&graph, &journey, 0), Ok(()));
+    assert_eq!(replay(&graph, &journey, 2), Err("leg 2 leaves 1 at 20, before the journey can board at 22".to_string()));
+}
+
+#[test]
+fn wrong_values_and_totals_are_caught() {
+    let graph = Graph::parse(TIMETABLE, Format::Text).unwrap();
+    let problems = audit(&graph, &answer("earliest", earliest(&graph), 29), 0).unwrap();
+    assert_eq!(problems, ["earliest answers 29 but the journey gives 30"]);
+
+    let text = report(&graph, "min-wait").to_json().to_string().replace("\"wait\":10", "\"wait\":5");
+    let problems = audit(&graph, &Json::parse(&text).unwrap(), 0).unwrap();
+    assert_eq!(problems, ["the journey says wait 5 but it's 10"]);
+}
+
+#[test]
+fn journeys_elsewhere_are_caught() {
+    let graph = Graph::parse(TIMETABLE, Format::Text).unwrap();
+    let mut journey = earliest(&graph);
+    journey.legs.pop();
+    let problems = audit(&graph, &answer("earliest", journey, 20), 0).unwrap();
+    assert_eq!(problems, ["asked from 0 to 2 but the journey goes from 0 to 1"]);
+}
+
+fn cost(graph: &Graph, weights: Generalized) -> String {
+    Report::collect(|stats| Answer::cost(graph, 0, 2, 0, weights, &Exclusions::none(), stats)).to_json().to_string()
+}
+
+#[test]
+fn cost_answers_hold_up() {
+    let generated = Generator { lines: 8, stops: 15, seed: 7, ..Generator::default() }.generate();
+    let weights = [
+        Generalized::default(),
+        Generalized { wait: Fixed(150), ride: Fixed(125), transfer: Fixed(0) },
+        Generalized { wait: Fixed(0), ride: Fixed(33), transfer: Fixed(1000) },
+    ];
+    for graph in [Graph::parse(TIMETABLE, Format::Text).unwrap(), generated.graph()] {
+        for weights in weights {
+            let json = Json::parse(&cost(&graph, weights)).unwrap();
+            assert_eq!(audit(&graph, &json, 0).unwrap(), Vec::<String>::new(), "{:?}", weights);
+        }
+    }
+}
+
+#[test]
+fn cost_breakdowns_are_worked_out_again() {
+    let graph = Graph::parse(TIMETABLE, Format::Text).unwrap();
+    let text = cost(&graph, Generalized::default());
+    assert!(text.contains("\"answer\":45,\"breakdown\":{\"wait\":10,\"ride\":20,\"transfers\":1,\"wait_cost\":20,"), "{}", text);
+    let check = |text: &str| audit(&graph, &Json::parse(text).unwrap(), 0);
+
+    assert_eq!(check(&text.replace("\"answer\":45", "\"answer\":40")).unwrap(), ["costs 40 but the journey gives 45"]);
+    assert_eq!(check(&text.replace("\"wait_cost\":20", "\"wait_cost\":10")).unwrap(), ["the breakdown says wait_cost 10 but it's 20"]);
+    // Other weights make it another cost altogether
+    assert_eq!(
+        check(&text.replace("\"wait\":2,", "\"wait\":1.5,")).unwrap(),
+        ["the breakdown says wait_cost 20 but it's 15", "the breakdown says cost 45 but it's 40", "costs 45 but the journey gives 40"]
+    );
+    assert_eq!(check(&text.replace("\"wait\":10,\"ride\":20,\"transfers\":1,\"legs", "\"wait\":0,\"ride\":20,\"transfers\":1,\"legs")).unwrap(), ["the journey says wait 0 but it's 10"]);
+    assert_eq!(check(&text.replace("\"departure\":20", "\"departure\":19")).unwrap(), ["leg 2 leaves 1 at 19, before the journey can board at 20"]);
+    assert_eq!(check(&text.replace(",\"answer\":45", ",\"answer\":\"45\"")), Err("\"answer\" must be a number or null".to_string()));
+
+    let none = cost(&Graph::parse("1 3\n0 1\n10 20\n", Format::Text).unwrap(), Generalized::default());
+    assert_eq!(check(&none).unwrap(), Vec::<String>::new());
+    assert_eq!(check(&none.replace("\"answer\":null", "\"answer\":12")).unwrap(), ["costs 12 without a journey"]);
+}
+
+#[test]
+fn cost_weights_must_be_ours() {
+    let graph = Graph::parse(TIMETABLE, Format::Text).unwrap();
+    let text = cost(&graph, Generalized::default());
+    let check = |text: &str| audit(&graph, &Json::parse(text).unwrap(), 0);
+    assert_eq!(
+        check(&text.replace("\"transfer\":5}", "\"transfer\":5,\"walk\":1.5}")),
+        Err("there are no footpaths in the timetable, so there is no walking to weigh".to_string())
+    );
+    assert_eq!(check(&text.replace("\"ride\":1,", "\"ride\":1.125,")), Err("weight \"ride\": '1.125' is not a number with at most two decimals".to_string()));
+    assert_eq!(check(&text.replace("\"ride\":1,", "\"ride\":-1,")), Err("weight \"ride\": '-1' is not a number with at most two decimals".to_string()));
+    assert_eq!(check(&text.replace("\"ride\":1,", "")), Err("the weights need \"ride\"".to_string()));
+}
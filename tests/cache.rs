@@ -0,0 +1,87 @@
+use std::env;
+use std::fs;
+
+use pathfinding::cache::FORMAT_VERSION;
+use pathfinding::csr::CsrGraph;
+use pathfinding::exclusions::Exclusions;
+use pathfinding::format::Format;
+use pathfinding::graph::Graph;
+use pathfinding::solvers;
+use pathfinding::solvers::stats::SearchStats;
+
+// Two bus lines, a frequency line and two calendars, so every section of the cache has something in it
+const TIMETABLE: &str = "\
+2 4 1
+0 1 2 3
+10 20 30 40
+0 2
+5 35
+1 3
+0 15
+10 0 60
+2
+1111100 20260101 20261231 -20261225
+0000011 20260101 20261231 +20261225
+0 1 0
+";
+
+fn frozen() -> CsrGraph {
+    Graph::parse(TIMETABLE, Format::Text).unwrap().freeze()
+}
+
+#[test]
+fn round_trips() {
+    let graph = frozen();
+    let bytes = graph.to_bytes();
+    let loaded = CsrGraph::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded.to_bytes(), bytes);
+    assert_eq!((loaded.edge_count(), loaded.trip_count(), loaded.trip_name(2)), (4, 3, "2"));
+
+    let none = Exclusions::none();
+    for name in ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest"] {
+        let (solve, _) = solvers::named::<CsrGraph>(name).unwrap();
+        let expected = solve(&graph, 0, 3, &none, &mut SearchStats::default());
+        assert_eq!(solve(&loaded, 0, 3, &none, &mut SearchStats::default()), expected, "{}", name);
+    }
+}
+
+#[test]
+fn saves_and_loads_files() {
+    let path = env::temp_dir().join(format!("pathfind-cache-{}.bin", std::process::id()));
+    frozen().save(&path).unwrap();
+    let loaded = CsrGraph::load(&path);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.unwrap().to_bytes(), frozen().to_bytes());
+}
+
+#[test]
+fn rejects_other_versions() {
+    let mut bytes = frozen().to_bytes();
+    bytes[8..12].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    let error = CsrGraph::from_bytes(&bytes).err().unwrap();
+    assert_eq!(
+        error,
+        format!("graph cache has format version {}, this build reads version {}; rebuild the cache from the timetable", FORMAT_VERSION + 1, FORMAT_VERSION)
+    );
+}
+
+#[test]
+fn rejects_flipped_bytes() {
+    let bytes = frozen().to_bytes();
+    for i in 20..bytes.len() {
+        let mut damaged = bytes.clone();
+        damaged[i] ^= 0x10;
+        assert_eq!(CsrGraph::from_bytes(&damaged).err().unwrap(), "graph cache checksum mismatch, the file is damaged", "byte {}", i);
+    }
+    let mut magic = bytes.clone();
+    magic[0] = b'X';
+    assert_eq!(CsrGraph::from_bytes(&magic).err().unwrap(), "not a graph cache file");
+}
+
+#[test]
+fn rejects_truncated_files() {
+    let bytes = frozen().to_bytes();
+    for length in 0..bytes.len() {
+        assert!(CsrGraph::from_bytes(&bytes[..length]).is_err(), "cut at {}", length);
+    }
+}
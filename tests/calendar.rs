@@ -0,0 +1,145 @@
+use pathfinding::calendar::{minutes, Calendar, Date, DateTime, Weekday, WeekdayMask};
+use pathfinding::exclusions::Exclusions;
+use pathfinding::graph::{Graph, EVERY_DAY};
+use pathfinding::json::Json;
+use pathfinding::report::{Answer, Report};
+use pathfinding::solvers::earliest::dijkstra_on;
+use pathfinding::solvers::stats::SearchStats;
+
+fn date(text: &str) -> Date {
+    text.parse().unwrap()
+}
+
+#[test]
+fn converts_civil_dates() {
+    assert_eq!(Date::from_ymd(1970, 1, 1), Some(Date::from_days(0)));
+    assert_eq!(Date::from_ymd(1969, 12, 31), Some(Date::from_days(-1)));
+    assert_eq!(Date::from_ymd(2000, 3, 1), Some(Date::from_days(11017)));
+    assert_eq!(Date::from_ymd(2026, 10, 18), Some(Date::from_days(20744)));
+    assert_eq!(Date::from_ymd(1600, 2, 29).map(Date::ymd), Some((1600, 2, 29)));
+
+    // 2000 is a leap year for being divisible by 400, 2100 isn't one for being divisible by 100
+    assert!(Date::from_ymd(2000, 2, 29).is_some());
+    assert_eq!(Date::from_ymd(2100, 2, 29), None);
+    assert_eq!(Date::from_ymd(2026, 4, 31), None);
+    assert_eq!(Date::from_ymd(2026, 13, 1), None);
+
+    // Every day of four centuries either side of the epoch comes back the way it went in
+    for days in -146097..146097 {
+        let (year, month, day) = Date::from_days(days).ymd();
+        assert_eq!(Date::from_ymd(year, month, day), Some(Date::from_days(days)));
+    }
+}
+
+#[test]
+fn parses_and_prints_dates() {
+    assert_eq!(date("2026-10-18"), date("20261018"));
+    assert_eq!(date("20261018").to_string(), "2026-10-18");
+    assert_eq!("2026-02-30".parse::<Date>(), Err("invalid date '2026-02-30'".to_string()));
+    assert_eq!("18-10-2026x".parse::<Date>(), Err("invalid date '18-10-2026x'".to_string()));
+    assert_eq!(DateTime::from_offset(date("2026-10-18"), -30).to_string(), "2026-10-17 23:30");
+    assert_eq!(DateTime::new(date("2026-10-18"), 1450).offset_from(date("2026-10-18")), 1450);
+    assert_eq!(minutes("25:05"), Some(1505));
+    assert_eq!(minutes("8:5"), None);
+}
+
+#[test]
+fn knows_the_weekday() {
+    assert_eq!(Date::from_days(0).weekday(), Weekday::Thursday);
+    assert_eq!(date("2026-10-18").weekday(), Weekday::Sunday);
+    assert_eq!(date("2000-01-01").weekday(), Weekday::Saturday);
+    assert_eq!(Date::from_days(-1).weekday(), Weekday::Wednesday);
+}
+
+#[test]
+fn reads_weekday_masks() {
+    assert_eq!("1111100".parse::<WeekdayMask>(), Ok(WeekdayMask::WORKDAYS));
+    assert_eq!("0000011".parse::<WeekdayMask>(), Ok(WeekdayMask::WEEKEND));
+    let mask = "1010000".parse::<WeekdayMask>().unwrap();
+    assert!(mask.contains(Weekday::Monday) && mask.contains(Weekday::Wednesday));
+    assert!(!mask.contains(Weekday::Tuesday) && !mask.contains(Weekday::Sunday));
+    assert!("111110".parse::<WeekdayMask>().is_err());
+    assert!("11111x0".parse::<WeekdayMask>().is_err());
+}
+
+#[test]
+fn honours_holiday_exceptions() {
+    // Workdays of 2026, but not on Christmas, and on the Saturday after it
+    let calendar = "1111100 20260101 20261231 -20261225 +20261226".parse::<Calendar>().unwrap();
+    assert!(calendar.runs_on(date("2026-12-24")));
+    assert!(!calendar.runs_on(date("2026-12-25")));
+    assert!(calendar.runs_on(date("2026-12-26")));
+    assert!(!calendar.runs_on(date("2026-12-27")));
+    assert!(!calendar.runs_on(date("2027-01-01")));
+    assert_eq!(calendar.next_service_day(date("2026-12-25")), Some(date("2026-12-26")));
+    assert_eq!(calendar.next_service_day(date("2026-12-27")), Some(date("2026-12-28")));
+    assert_eq!(calendar.next_service_day(date("2027-01-01")), None);
+    assert!("1111100 20260101".parse::<Calendar>().is_err());
+    assert!("1111100 20260101 20261231 20261225".parse::<Calendar>().is_err());
+}
+
+#[test]
+fn looks_past_every_removed_week() {
+    // Mondays only, with three Mondays in a row removed: the next run is 3 weeks after the first Monday
+    let calendar = "1000000 20260101 20261231 -20261019 -20261026 -20261102".parse::<Calendar>().unwrap();
+    assert_eq!(calendar.next_service_day(date("2026-10-18")), Some(date("2026-11-09")));
+    // Before the calendar starts the search starts with it
+    assert_eq!(calendar.next_service_day(date("2025-06-01")), Some(date("2026-01-05")));
+    // With the last Monday of the year removed too there's nothing left
+    let last = "1000000 20260101 20261231 -20261228".parse::<Calendar>().unwrap();
+    assert_eq!(last.next_service_day(date("2026-12-22")), None);
+}
+
+// Trip 0 runs every day from 0 to 1 at 23:00 - 23:30, the one from 1 to 2 only runs on workdays
+fn overnight(second: (&[u32], &[u32])) -> Graph {
+    let mut graph = Graph::new(3);
+    graph.calendars.push("1111100 20260101 20261231".parse().unwrap());
+    graph.add_trip(&[0, 1], &[1380, 1410], EVERY_DAY);
+    graph.add_trip(second.0, second.1, 1);
+    graph
+}
+
+fn arrive(graph: &Graph, departure: &str, at: u32) -> Option<String> {
+    let departure = DateTime::new(date(departure), at);
+    dijkstra_on(graph, 0, 2, departure, &Exclusions::none(), &mut SearchStats::default()).map(|journey| journey.legs.last().unwrap().arrival.to_string())
+}
+
+#[test]
+fn crosses_midnight_into_the_next_service_day() {
+    // Leaving 1 at 00:10 the next morning, which is a Tuesday for a Monday evening
+    let graph = overnight((&[1, 2], &[10, 40]));
+    assert_eq!(arrive(&graph, "2026-10-19", 22 * 60), Some("2026-10-20 00:40".to_string()));
+    // Friday evening has to wait for Monday morning
+    assert_eq!(arrive(&graph, "2026-10-16", 22 * 60), Some("2026-10-19 00:40".to_string()));
+
+    // Written as 24:10, the run belongs to the service day before: Friday's runs on Saturday morning
+    let late = overnight((&[1, 2], &[1450, 1480]));
+    assert_eq!(arrive(&late, "2026-10-16", 22 * 60), Some("2026-10-17 00:40".to_string()));
+    // and Saturday's doesn't run at all, so Sunday evening waits for Monday's run on Tuesday morning
+    assert_eq!(arrive(&late, "2026-10-18", 22 * 60), Some("2026-10-20 00:40".to_string()));
+    // Past the end of the calendar nothing runs on from 1
+    assert_eq!(arrive(&late, "2027-01-04", 22 * 60), None);
+}
+
+#[test]
+fn dated_journeys_keep_the_day_of_every_leg() {
+    // Friday evening's run to 1, then Monday morning's on to 2
+    let graph = overnight((&[1, 2], &[10, 40]));
+    let departure = DateTime::new(date("2026-10-16"), 22 * 60);
+    let report = Report::collect(|stats| Answer::Dated {
+        from: 0,
+        to: 2,
+        departure,
+        journey: dijkstra_on(&graph, 0, 2, departure, &Exclusions::none(), stats),
+    });
+    let printed = ["2026-10-19 00:40", "  0 -> 1  2026-10-16 23:00 - 2026-10-16 23:30  trip 0", "  1 -> 2  2026-10-19 00:10 - 2026-10-19 00:40  trip 1"];
+    assert_eq!(report.to_string(), printed.join("\n"));
+
+    let json = report.to_json();
+    assert_eq!(json.get("answer").and_then(Json::as_str), Some("2026-10-19 00:40"));
+    let journey = json.get("journey").unwrap();
+    assert_eq!(journey.get("start").and_then(Json::as_str), Some("2026-10-16 22:00"));
+    let legs = journey.get("legs").and_then(Json::as_array).unwrap();
+    let times = legs.iter().map(|leg| (leg.get("departure").and_then(Json::as_str), leg.get("trip").and_then(Json::as_u32))).collect::<Vec<_>>();
+    assert_eq!(times, vec![(Some("2026-10-16 23:00"), Some(0)), (Some("2026-10-19 00:10"), Some(1))]);
+}
@@ -0,0 +1,77 @@
+// Each test uses only some of these
+#![allow(dead_code)]
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use pathfinding::audit::replay;
+use pathfinding::generate::Generated;
+use pathfinding::graph::{Graph, TripId, Vertex};
+use pathfinding::input::Input;
+use pathfinding::journey::Journey;
+
+// What the tests over fixtures share
+
+pub fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+// realtime.txt, see tests/gtfs_rt.rs
+pub fn timetable() -> Graph {
+    let file = File::open(fixture("realtime.txt")).unwrap();
+    Graph::read(&mut Input::new(BufReader::new(file))).unwrap()
+}
+
+// Every leg of the trip as it runs now: departure, arrival and whether it's cancelled
+pub fn legs(graph: &Graph, trip: TripId) -> Vec<(u32, u32, bool)> {
+    let trip = &graph.trips[trip as usize];
+    let edges = trip.stops.iter().zip(&trip.legs).map(|(&stop, &leg)| graph.adjacency[stop as usize][leg]);
+    edges.map(|edge| (edge.departure_time, edge.arrival(), edge.cancelled)).collect()
+}
+
+// What the tests over random timetables share
+
+// A journey a solver returns has to be one we can actually make
+pub fn feasible(graph: &Graph, journey: &Journey, start: Vertex, end: Vertex) -> Result<(), String> {
+    if journey.origin != start || journey.destination() != end {
+        return Err(format!("goes from {} to {}", journey.origin, journey.destination()));
+    }
+    replay(graph, journey, 0)
+}
+
+// Smaller versions of the instance: a line less, a line a stop shorter, or a line earlier
+fn smaller(instance: &Generated) -> Vec<Generated> {
+    let mut candidates = vec![];
+    let with = |i: usize, line: Option<(Vec<Vertex>, Vec<u32>)>| {
+        let mut lines = instance.lines.clone();
+        match line {
+            Some(line) => lines[i] = line,
+            None => {
+                lines.remove(i);
+            }
+        }
+        Generated { stops: instance.stops, lines }
+    };
+    for (i, (locations, departures)) in instance.lines.iter().enumerate() {
+        candidates.push(with(i, None));
+        if locations.len() > 1 {
+            candidates.push(with(i, Some((locations[1..].to_vec(), departures[1..].to_vec()))));
+            let last = locations.len() - 1;
+            candidates.push(with(i, Some((locations[..last].to_vec(), departures[..last].to_vec()))));
+        }
+        if departures[0] > 0 {
+            let shift = departures[0].div_ceil(2);
+            candidates.push(with(i, Some((locations.clone(), departures.iter().map(|time| time - shift).collect()))));
+        }
+    }
+    candidates
+}
+
+// The smallest instance `check` still finds something wrong with, going one smaller version at a time
+pub fn shrink(mut instance: Generated, check: impl Fn(&Generated) -> Option<String>) -> Generated {
+    while let Some(next) = smaller(&instance).into_iter().find(|candidate| check(candidate).is_some()) {
+        instance = next;
+    }
+    instance
+}
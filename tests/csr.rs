@@ -0,0 +1,93 @@
+use pathfinding::exclusions::Exclusions;
+use pathfinding::generate::Generator;
+use pathfinding::graph::{Edge, Graph, Timetable, Vertex, EVERY_DAY};
+use pathfinding::realtime::{Status, Update};
+
+/*
+ * Freezing a graph into CSR form keeps every leg that runs, sorted by departure at each stop, and
+ * the frozen graph answers legs_from and departures the same as the graph it came from.
+ */
+
+// Departure, destination and trip, enough to tell legs apart
+fn key(edge: &Edge) -> (u32, Vertex, u32, u32) {
+    (edge.departure_time, edge.to, edge.trip, edge.weight)
+}
+
+fn graph(seed: u64) -> Graph {
+    let mut graph = Generator { lines: 6, stops: 12, span: (0, 120), seed, ..Generator::default() }.generate().graph();
+    graph.add_frequency(&[0, 5, 11], &[0, 7, 20], 15, (30, 90), EVERY_DAY);
+    graph
+}
+
+#[test]
+fn keeps_every_leg_sorted_by_departure() {
+    for seed in 0..20 {
+        let graph = graph(seed);
+        let frozen = graph.freeze();
+        assert_eq!(frozen.stop_count(), graph.stop_count());
+        assert_eq!(frozen.edge_count(), graph.adjacency.iter().map(Vec::len).sum::<usize>());
+        for stop in 0..graph.stop_count() as Vertex {
+            let legs = frozen.edges(stop).iter().map(key).collect::<Vec<_>>();
+            assert!(legs.windows(2).all(|pair| pair[0].0 <= pair[1].0), "seed {} stop {}", seed, stop);
+            let mut expected = graph.adjacency[stop as usize].iter().map(key).collect::<Vec<_>>();
+            expected.sort();
+            let mut sorted = legs.clone();
+            sorted.sort();
+            assert_eq!(sorted, expected, "seed {} stop {}", seed, stop);
+            assert_eq!(frozen.frequencies_from(stop).len(), graph.frequencies[stop as usize].len());
+        }
+    }
+}
+
+#[test]
+fn keeps_what_it_knows_about_trips() {
+    let mut graph = graph(3);
+    graph.trips[1].name = "night bus".to_string();
+    graph.trips[2].line = 0;
+    let frozen = graph.freeze();
+    assert_eq!(frozen.trip_count(), graph.trips.len());
+    for (id, trip) in graph.trips.iter().enumerate() {
+        assert_eq!(frozen.trip_name(id as u32), trip.name);
+        assert_eq!(frozen.line(id as u32), trip.line);
+        assert_eq!(format!("{:?}", frozen.service(id as u32)), format!("{:?}", graph.service(id as u32)));
+    }
+}
+
+#[test]
+fn answers_departures_like_the_graph() {
+    let none = Exclusions::none();
+    for seed in 0..20 {
+        let graph = graph(seed);
+        let frozen = graph.freeze();
+        for stop in 0..graph.stop_count() as Vertex {
+            for time in (0..150).step_by(7) {
+                let sorted = |legs: Vec<Edge>| {
+                    let mut legs = legs.iter().map(key).collect::<Vec<_>>();
+                    legs.sort();
+                    legs
+                };
+                let legs = sorted(graph.legs_from(stop, time).copied().collect());
+                assert_eq!(sorted(frozen.legs_from(stop, time).copied().collect()), legs, "seed {} stop {} at {}", seed, stop, time);
+                assert!(legs.iter().all(|&(departure, ..)| departure >= time));
+                let departures = sorted(graph.departures(stop, time, &none).collect());
+                assert_eq!(sorted(frozen.departures(stop, time, &none).collect()), departures, "seed {} stop {} at {}", seed, stop, time);
+            }
+        }
+    }
+}
+
+#[test]
+fn leaves_out_cancelled_legs_and_keeps_delays() {
+    let mut graph = graph(5);
+    let trip = &graph.trips[0];
+    let (stop, leg) = (trip.stops[0], trip.legs[0]);
+    let planned = graph.adjacency[stop as usize][leg];
+    graph.apply(&Update { trip: 0, stop_index: 0, status: Status::Delayed(600) }).unwrap();
+    let delayed = graph.freeze();
+    assert!(delayed.edges(stop).iter().any(|edge| edge.trip == 0 && edge.departure_time == planned.departure_time + 10));
+
+    graph.apply(&Update { trip: 0, stop_index: 0, status: Status::Cancelled }).unwrap();
+    let cancelled = graph.freeze();
+    assert_eq!(cancelled.edge_count(), delayed.edge_count() - (graph.trips[0].stops.len() - 1));
+    assert!(cancelled.edges(stop).iter().all(|edge| edge.trip != 0));
+}
@@ -0,0 +1,84 @@
+use pathfinding::exclusions::Exclusions;
+use pathfinding::graph::Graph;
+use pathfinding::input::Input;
+use pathfinding::solvers::stats::SearchStats;
+use pathfinding::solvers::{self, Solver};
+
+const TEXT: &str = "\
+3 4
+0 1 3
+10 20 30
+0 2 3
+5 15 50
+2 3
+16 40
+";
+
+// The same timetable with the rows out of order, quoted fields and a mix of time formats
+const CSV: &str = "\
+line_id,seq,stop,time
+\"red, via 1\",3,3,0:30
+\"red, via 1\",1,0,10
+blue,2,2,15
+
+blue,1,0,0:05
+\"red, via 1\",2,1,20
+\"say \"\"green\"\"\",7,2,16
+blue,3,3,50
+\"say \"\"green\"\"\",9,3,0:40
+";
+
+#[test]
+fn builds_the_graph_the_text_format_does() {
+    let text = Graph::read(&mut Input::new(TEXT.as_bytes())).unwrap();
+    let csv = Graph::from_csv(CSV).unwrap();
+    assert_eq!(csv.to_string(), text.to_string());
+    let stops = |graph: &Graph| graph.trips.iter().map(|trip| trip.stops.clone()).collect::<Vec<_>>();
+    assert_eq!(stops(&csv), stops(&text));
+    let names = csv.trips.iter().map(|trip| trip.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["red, via 1", "blue", "say \"green\""]);
+
+    for name in ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest"] {
+        let (solve, _): (Solver, _) = solvers::named(name).unwrap();
+        let answer = |graph: &Graph| solve(graph, 0, 3, &Exclusions::none(), &mut SearchStats::default());
+        assert_eq!(answer(&csv), answer(&text), "{}", name);
+    }
+}
+
+#[test]
+fn visits_stops_in_order_of_seq() {
+    // Sorted as numbers, so 10 comes after 9
+    let graph = Graph::from_csv("a,10,2,30\na,9,1,20\na,1,0,10\n").unwrap();
+    assert_eq!(graph.trips[0].stops, [0, 1, 2]);
+    let legs = graph.adjacency.iter().flatten().map(|edge| (edge.departure_time, edge.arrival())).collect::<Vec<_>>();
+    assert_eq!(legs, [(10, 20), (20, 30)]);
+}
+
+#[test]
+fn the_header_is_optional() {
+    let with = Graph::from_csv("line_id,seq,stop,time\na,1,0,10\na,2,1,20\n").unwrap();
+    let without = Graph::from_csv("a,1,0,10\na,2,1,20\n").unwrap();
+    assert_eq!(with.to_string(), without.to_string());
+    // Blank lines before it are fine, but a header anywhere after the first row is just a bad row
+    let blank_first = Graph::from_csv("\n  \nline_id,seq,stop,time\na,1,0,10\na,2,1,20\n").unwrap();
+    assert_eq!(blank_first.to_string(), without.to_string());
+    let late = Graph::from_csv("a,1,0,10\nline_id,seq,stop,time\na,2,1,20\n");
+    assert_eq!(late.err(), Some("row 2: seq 'seq' is not a number".to_string()));
+}
+
+#[test]
+fn errors_carry_the_row() {
+    let cases = [
+        ("line_id,seq,stop,time\na,1,0,10\na,2,1\n", "row 3: expected line_id,seq,stop,time but found 3 fields"),
+        ("a,1,0,10\n\"a,2,1,20\n", "row 2: unterminated quote"),
+        ("a,first,0,10\n", "row 1: seq 'first' is not a number"),
+        ("a,1,-1,10\n", "row 1: stop '-1' is not a number"),
+        ("a,1,0,10\na,2,1,8:75\n", "row 2: time '8:75' is not minutes or H:MM"),
+        ("a,1,0,10\n\na,1,1,20\n", "row 3: line a visits seq 1 twice"),
+        ("a,2,1,20\nb,1,0,0\na,1,0,30\n", "row 1: line a leaves stop 1 at 20, before it got there at 30"),
+        ("a,1,0,10\na,2,99999999,20\n", "row 2: 100000000 stops, more than the 1048576 we can hold"),
+    ];
+    for (text, error) in cases {
+        assert_eq!(Graph::from_csv(text).err(), Some(error.to_string()), "{:?}", text);
+    }
+}
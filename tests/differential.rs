@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use pathfinding::exclusions::Exclusions;
+use pathfinding::generate::Generated;
+use pathfinding::graph::{Graph, Timetable, Vertex};
+use pathfinding::journey::{Journey, Leg};
+use pathfinding::solvers::cost::{Fixed, Generalized, MinTransfers};
+use pathfinding::solvers::label::{self, CostModel};
+use pathfinding::solvers::stats::SearchStats;
+use pathfinding::solvers::{self, Solver};
+
+use common::{feasible, shrink};
+
+mod common;
+
+/*
+ * Every solver against a brute force enumeration of all journeys, on thousands of tiny random
+ * timetables going from Mercator (0) to home (the last stop) like the assignments do. Lines may
+ * visit a stop twice and buses may take no time at all between stops. When a solver disagrees the
+ * instance is shrunk as far as it keeps failing and printed in the assignment format.
+ */
+
+const INSTANCES: u64 = 3000;
+
+// Weights with decimals, so the costs don't come out whole
+const GENERALIZED: Generalized = Generalized { wait: Fixed(200), ride: Fixed(150), transfer: Fixed(525) };
+
+// Objectives of all the journeys home, a journey ending when it first gets there
+struct Best {
+    arrival: Option<u32>,
+    min_wait: Option<u32>,
+    max_wait: Option<u32>,
+    min_transfers: Option<(usize, u32)>,
+    generalized: Option<Fixed>,
+}
+
+// In hundredths of a minute
+fn generalized(journey: &Journey) -> Fixed {
+    Fixed(journey.wait_time() as u64 * 200 + journey.ride_time() as u64 * 150 + journey.transfers() as u64 * 525)
+}
+
+fn brute_force(graph: &Graph, start: Vertex, end: Vertex) -> Best {
+    fn walk(graph: &Graph, journey: &mut Journey, end: Vertex, used: &mut HashSet<Leg>, best: &mut Best) {
+        if journey.destination() == end {
+            let (arrival, wait) = (journey.arrival(), journey.wait_time());
+            best.arrival = Some(best.arrival.map_or(arrival, |best| best.min(arrival)));
+            best.min_wait = Some(best.min_wait.map_or(wait, |best| best.min(wait)));
+            best.max_wait = Some(best.max_wait.map_or(wait, |best| best.max(wait)));
+            let transfers = (journey.transfers(), arrival);
+            best.min_transfers = Some(best.min_transfers.map_or(transfers, |best| best.min(transfers)));
+            let cost = generalized(journey);
+            best.generalized = Some(best.generalized.map_or(cost, |best| best.min(cost)));
+            return;
+        }
+        // Not taking the same bus twice keeps rides that take no time from going round forever
+        let none = Exclusions::none();
+        for edge in graph.every_departure(journey.destination(), journey.arrival(), &none) {
+            let leg = Leg::new(journey.destination(), &edge);
+            if used.insert(leg) {
+                journey.legs.push(leg);
+                walk(graph, journey, end, used, best);
+                journey.legs.pop();
+                used.remove(&leg);
+            }
+        }
+    }
+
+    let mut best = Best { arrival: None, min_wait: None, max_wait: None, min_transfers: None, generalized: None };
+    let mut journey = Journey { origin: start, start: 0, legs: vec![] };
+    walk(graph, &mut journey, end, &mut HashSet::new(), &mut best);
+    best
+}
+
+// What's wrong with the answers on this instance, if anything
+fn check(instance: &Generated) -> Option<String> {
+    let graph = instance.graph();
+    let (start, end) = (0, instance.stops - 1);
+    let best = brute_force(&graph, start, end);
+
+    let expectations: [(&str, Option<u32>); 5] = [
+        ("earliest", best.arrival),
+        ("min-wait", best.min_wait),
+        ("min-transfers", best.min_transfers.map(|(transfers, _)| transfers as u32)),
+        ("max-wait", best.max_wait),
+        ("dag-longest", best.max_wait),
+    ];
+    for (name, expected) in expectations {
+        let (solve, objective): (Solver, _) = solvers::named(name).unwrap();
+        let journey = solve(&graph, start, end, &Exclusions::none(), &mut SearchStats::default());
+        if let Some(Err(e)) = journey.as_ref().map(|journey| feasible(&graph, journey, start, end)) {
+            return Some(format!("{} returned an impossible journey, it {}:\n{}", name, e, journey.unwrap()));
+        }
+        let found = journey.as_ref().map(objective);
+        if found != expected {
+            return Some(format!("{} answered {:?} where brute force found {:?}", name, found, expected));
+        }
+    }
+    if let Some(e) = check_model(&graph, start, end, "min-transfers", &MinTransfers, best.min_transfers) {
+        return Some(e);
+    }
+    if let Some(e) = check_model(&graph, start, end, "generalized", &GENERALIZED, best.generalized) {
+        return Some(e);
+    }
+    // The breakdown has to add up to what the search found
+    let (journey, cost) = label::search(&graph, start, end, 0, &Exclusions::none(), &GENERALIZED, &mut SearchStats::default())?;
+    let breakdown = GENERALIZED.breakdown(&journey);
+    (breakdown.cost() != cost).then(|| format!("generalized cost {:?} but its breakdown adds up to {:?}", cost, breakdown))
+}
+
+// The label setting search has to find the cheapest journey, and say what it costs
+fn check_model<M: CostModel<u32>>(graph: &Graph, start: Vertex, end: Vertex, name: &str, model: &M, expected: Option<M::Cost>) -> Option<String> {
+    let found = label::search(graph, start, end, 0, &Exclusions::none(), model, &mut SearchStats::default());
+    if let Some(Err(e)) = found.as_ref().map(|(journey, _)| feasible(graph, journey, start, end)) {
+        return Some(format!("{} returned an impossible journey, it {}:\n{}", name, e, found.unwrap().0));
+    }
+    let cost = found.as_ref().map(|&(_, cost)| cost);
+    if cost != expected {
+        return Some(format!("{} answered {:?} where brute force found {:?}", name, cost, expected));
+    }
+    None
+}
+
+fn random_instance(rng: &mut StdRng) -> Generated {
+    let stops = rng.random_range(2..=5);
+    let lines = (0..rng.random_range(1..=5))
+        .map(|_| {
+            let length = rng.random_range(1..=4);
+            let locations = (0..length).map(|_| rng.random_range(0..stops)).collect::<Vec<Vertex>>();
+            let mut time = rng.random_range(0..20);
+            let departures = (0..length)
+                .map(|_| {
+                    time += rng.random_range(0..=6);
+                    time
+                })
+                .collect();
+            (locations, departures)
+        })
+        .collect();
+    Generated { stops, lines }
+}
+
+#[test]
+fn solvers_agree_with_brute_force() {
+    for seed in 0..INSTANCES {
+        let instance = random_instance(&mut StdRng::seed_from_u64(seed));
+        if check(&instance).is_some() {
+            let minimal = shrink(instance, check);
+            panic!("seed {}: {}\nsmallest failing instance:\n{}", seed, check(&minimal).unwrap(), minimal);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::path::Path;
+use std::process::Command;
+
+use pathfinding::exclusions::{Exclusions, WhatIf};
+use pathfinding::graph::{Graph, EVERY_DAY};
+use pathfinding::journey::Journey;
+use pathfinding::json::Json;
+use pathfinding::repl::Session;
+use pathfinding::solvers::cost::{Earliest, Generalized, MinTransfers, MinWait};
+use pathfinding::solvers::label::{self, CostModel};
+use pathfinding::solvers::stats::SearchStats;
+use pathfinding::solvers::{self, earliest, profile, Solver};
+
+/*
+ * Trips 0 and 1 are runs of the same line going 0 -> 1 -> 3, leaving at 10 and 20. Trip 2 is a line of its
+ * own going 0 -> 2 -> 3, leaving at 15 and getting home last at 50. Whatever is excluded, trip 2 is left.
+ */
+fn timetable() -> Graph {
+    let mut graph = Graph::new(4);
+    graph.add_trip(&[0, 1, 3], &[10, 20, 30], EVERY_DAY);
+    let trip = graph.add_trip(&[0, 1, 3], &[20, 30, 40], EVERY_DAY);
+    graph.trips[trip as usize].line = 0;
+    graph.add_trip(&[0, 2, 3], &[15, 25, 50], EVERY_DAY);
+    graph
+}
+
+fn exclusions() -> [(&'static str, Exclusions, u32); 4] {
+    [
+        ("nothing", Exclusions::none(), 30),
+        ("trip 0", Exclusions::none().cancel_trip(0), 40),
+        ("line 0", Exclusions::none().cancel_line(0), 50),
+        ("stop 1", Exclusions::none().close_stop(1), 50),
+    ]
+}
+
+// Whether the journey rides anything the exclusions took away
+fn excluded(graph: &Graph, journey: &Journey, exclusions: &Exclusions) -> bool {
+    journey.legs.iter().any(|leg| {
+        [leg.from, leg.to].iter().any(|stop| exclusions.stops.contains(stop))
+            || exclusions.trips.contains(&leg.trip)
+            || exclusions.lines.contains(&graph.trips[leg.trip as usize].line)
+    })
+}
+
+fn model<M: CostModel<u32>>(graph: &Graph, exclusions: &Exclusions, model: &M) -> Option<Journey> {
+    label::search(graph, 0, 3, 0, exclusions, model, &mut SearchStats::default()).map(|(journey, _)| journey)
+}
+
+#[test]
+fn every_solver_respects_every_exclusion() {
+    let graph = timetable();
+    for (what, exclusions, arrival) in exclusions() {
+        let mut journeys = vec![];
+        for name in ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest"] {
+            let (solve, _): (Solver, _) = solvers::named(name).unwrap();
+            journeys.push((name, solve(&graph, 0, 3, &exclusions, &mut SearchStats::default())));
+        }
+        journeys.push(("earliest model", model(&graph, &exclusions, &Earliest)));
+        journeys.push(("min-wait model", model(&graph, &exclusions, &MinWait)));
+        journeys.push(("min-transfers model", model(&graph, &exclusions, &MinTransfers)));
+        journeys.push(("generalized", model(&graph, &exclusions, &Generalized::default())));
+        for journey in profile::profile(&graph, 0, 3, &exclusions, &mut SearchStats::default()) {
+            journeys.push(("profile", Some(journey)));
+        }
+
+        for (name, journey) in journeys {
+            let journey = journey.unwrap_or_else(|| panic!("{} found nothing without {}", name, what));
+            assert!(!excluded(&graph, &journey, &exclusions), "{} without {} took\n{}", name, what, journey);
+        }
+        let fastest = earliest::dijkstra(&graph, 0, 3, &exclusions, &mut SearchStats::default());
+        assert_eq!(fastest.map(|journey| journey.arrival()), Some(arrival), "without {}", what);
+    }
+}
+
+#[test]
+fn nothing_is_left_when_everything_is_excluded() {
+    let graph = timetable();
+    let exclusions = Exclusions::none().cancel_line(0).close_stop(2);
+    for name in ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest"] {
+        let (solve, _): (Solver, _) = solvers::named(name).unwrap();
+        assert_eq!(solve(&graph, 0, 3, &exclusions, &mut SearchStats::default()), None, "{}", name);
+    }
+    assert_eq!(profile::profile(&graph, 0, 3, &exclusions, &mut SearchStats::default()), vec![]);
+}
+
+#[test]
+fn what_if_reports_the_changed_journey() {
+    let graph = timetable();
+    let whatif = WhatIf::run(&graph, 0, 3, &Exclusions::none().close_stop(1), earliest::dijkstra);
+    assert_eq!(whatif.arrival_delay(), Some(20));
+    assert_eq!(whatif.removed().iter().map(|leg| leg.trip).collect::<Vec<_>>(), vec![0, 0]);
+    assert_eq!(whatif.added().iter().map(|leg| (leg.from, leg.to)).collect::<Vec<_>>(), vec![(0, 2), (2, 3)]);
+    assert_eq!(
+        whatif.to_string(),
+        "baseline:   arrives 30, waits 10\n\
+         restricted: arrives 50, waits 15 (+20)\n\
+         - 0 -> 1  10 - 20  trip 0\n\
+         - 1 -> 3  20 - 30  trip 0\n\
+         + 0 -> 2  15 - 25  trip 2\n\
+         + 2 -> 3  25 - 50  trip 2"
+    );
+
+    let gone = WhatIf::run(&graph, 0, 3, &Exclusions::none().cancel_line(0).cancel_trip(2), earliest::dijkstra);
+    assert_eq!(gone.arrival_delay(), None);
+    assert_eq!(gone.added(), vec![]);
+    assert!(gone.to_string().contains("restricted: no journey"));
+}
+
+#[test]
+fn repl_shows_what_if_after_every_change() {
+    let mut session = Session::new(timetable());
+    assert_eq!(session.execute("whatif 0 3").unwrap(), "baseline:   arrives 30, waits 10\nrestricted: arrives 30, waits 10 (+0)");
+    let cancelled = session.execute("cancel trip 0").unwrap();
+    assert!(cancelled.starts_with("trip 0 is cancelled\nbaseline:   arrives 30, waits 10\nrestricted: arrives 40, waits 20 (+10)"));
+    assert!(session.execute("cancel line 0").unwrap().contains("restricted: arrives 50"));
+    assert!(session.execute("restore line 0").unwrap().contains("restricted: arrives 40"));
+    assert!(session.execute("closed 1").unwrap().contains("restricted: arrives 50"));
+    assert_eq!(session.execute("cancel line 7"), Err("there is no line '7'".to_string()));
+    assert_eq!(session.execute("whatif 0 3 fastest"), Err("there is no solver 'fastest'".to_string()));
+}
+
+// What pathfind prints asked `args` about tests/fixtures/realtime.txt, where line 0 runs 0 -> 1 -> 2 -> 3
+// getting home at 40 and trip 2 goes straight to 3 at 42
+fn pathfind(args: &[&str]) -> String {
+    let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/realtime.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_pathfind")).args(args).arg("--input").arg(input).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn command_line_shows_what_if_when_anything_is_excluded() {
+    assert_eq!(pathfind(&["earliest"]), "40\n");
+    let changed = "baseline:   arrives 40, waits 10\n\
+                   restricted: arrives 44, waits 42 (+4)\n\
+                   - 0 -> 1  10 - 20  trip 0\n\
+                   - 1 -> 2  20 - 30  trip 0\n\
+                   - 2 -> 3  30 - 40  trip 0\n\
+                   + 0 -> 3  42 - 44  trip 2\n";
+    for excluded in [["--closed", "1"], ["--cancel-line", "0"], ["--cancel-trip", "0"]] {
+        assert_eq!(pathfind(&[&["earliest"][..], &excluded].concat()), changed, "{:?}", excluded);
+    }
+
+    let json = Json::parse(&pathfind(&["min-wait", "--closed", "2", "--output", "json"])).unwrap();
+    assert_eq!(json.get("solver").and_then(Json::as_str), Some("min-wait"));
+    assert_eq!(json.get("baseline").and_then(|journey| journey.get("arrival")).and_then(Json::as_u32), Some(40));
+    assert_eq!(json.get("restricted").and_then(|journey| journey.get("arrival")).and_then(Json::as_u32), Some(44));
+    assert_eq!(json.get("delay").and_then(Json::as_u32), Some(4));
+    let trips = |key: &str| json.get(key).and_then(Json::as_array).unwrap().iter().map(|leg| leg.get("trip").and_then(Json::as_u32)).collect::<Vec<_>>();
+    assert_eq!(trips("removed"), vec![Some(0); 3]);
+    assert_eq!(trips("added"), vec![Some(2)]);
+}
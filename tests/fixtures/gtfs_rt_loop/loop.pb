@@ -0,0 +1,6 @@
+
+
+2.01
+e1+
+
+0<"0x"2"0"2
\ No newline at end of file
@@ -0,0 +1,13 @@
+
+
+2.0
+e1
+
+early
+(<
+e2
+
+latex
+e3
+
+early<
\ No newline at end of file
@@ -0,0 +1,63 @@
+use pathfinding::graph::FrequencyEdge;
+
+/*
+ * Frequency lines at the edges of their window: the first run, the last one, and times past it. The runs
+ * are also listed out one by one to check next_departure against.
+ */
+
+fn line<T: Copy>(offset: T, headway: T, first: T, last: T) -> FrequencyEdge<T> {
+    FrequencyEdge { to: 1, weight: headway, offset, headway, first, last, trip: 0 }
+}
+
+#[test]
+fn the_window_includes_its_last_run() {
+    let edge = line(5u32, 10, 0, 60);
+    assert_eq!(edge.last_departure(), 65);
+    let next = [0, 5, 6, 15, 64, 65, 66, 1000].map(|time| edge.next_departure(time));
+    assert_eq!(next, [Some(5), Some(5), Some(15), Some(15), Some(65), Some(65), None, None]);
+}
+
+#[test]
+fn a_window_off_the_headway_ends_on_the_run_before() {
+    // Runs at 0, 10, ..., 50, the window closing at 55 doesn't make another one
+    let edge = line(0u32, 10, 0, 55);
+    assert_eq!(edge.last_departure(), 50);
+    assert_eq!(edge.next_departure(50), Some(50));
+    assert_eq!(edge.next_departure(51), None);
+    assert_eq!(edge.next_departure(55), None);
+}
+
+#[test]
+fn a_single_run() {
+    let edge = line(3u32, 15, 480, 480);
+    assert_eq!(edge.last_departure(), 483);
+    assert_eq!(edge.next_departure(0), Some(483));
+    assert_eq!(edge.next_departure(483), Some(483));
+    assert_eq!(edge.next_departure(484), None);
+}
+
+#[test]
+fn the_last_run_at_the_end_of_time() {
+    let edge = line(5u16, 10, 65500, 65530);
+    assert_eq!(edge.last_departure(), 65535);
+    assert_eq!(edge.next_departure(65526), Some(65535));
+    assert_eq!(edge.next_departure(u16::MAX), Some(65535));
+
+    // The last run would only get here after the end of time, so it doesn't
+    let past = line(10u16, 10, 65500, 65530);
+    assert_eq!(past.next_departure(65531), None);
+    assert_eq!(past.next_departure(65530), Some(65530));
+}
+
+#[test]
+fn next_departure_is_the_first_run_listed() {
+    for (offset, headway, first, last) in [(0u32, 1, 0, 0), (0, 7, 3, 40), (4, 6, 10, 10), (9, 5, 20, 44), (2, 30, 0, 59)] {
+        let edge = line(offset, headway, first, last);
+        let runs = (first..=last).step_by(headway as usize).map(|start| start + offset).collect::<Vec<_>>();
+        assert_eq!(edge.last_departure(), *runs.last().unwrap(), "{:?}", edge);
+        for time in 0..=last + offset + headway {
+            let listed = runs.iter().copied().find(|&run| run >= time);
+            assert_eq!(edge.next_departure(time), listed, "{:?} at {}", edge, time);
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::fs;
+use std::path::Path;
+
+use pathfinding::exclusions::Exclusions;
+use pathfinding::format::Format;
+use pathfinding::graph::{Graph, Timetable, Vertex};
+use pathfinding::solvers::earliest;
+use pathfinding::solvers::stats::SearchStats;
+
+/*
+ * The seed corpus of the parse fuzz target run through what the target does, so the inputs that once
+ * crashed it keep being tried without nightly and cargo fuzz. See fuzz/fuzz_targets/parse.rs.
+ */
+
+#[test]
+fn parse_corpus_loads_or_fails_cleanly() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/parse");
+    let mut inputs = 0;
+    for entry in fs::read_dir(&directory).unwrap() {
+        let bytes = fs::read(entry.unwrap().path()).unwrap();
+        let Ok(text) = std::str::from_utf8(&bytes) else {
+            continue;
+        };
+        inputs += 1;
+        for format in [Format::Text, Format::Json, Format::Csv] {
+            let Ok(graph) = Graph::parse(text, format) else {
+                continue;
+            };
+            if graph.stop_count() == 0 {
+                continue;
+            }
+            let home = graph.stop_count() as Vertex - 1;
+            for at in [0, u32::MAX] {
+                let journey = earliest::dijkstra_from(&graph, 0, home, at, &Exclusions::none(), &mut SearchStats::default());
+                assert!(journey.is_none_or(|journey| journey.arrival() >= at));
+            }
+        }
+    }
+    assert!(inputs > 0, "no inputs in {}", directory.display());
+}
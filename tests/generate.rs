@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+
+use pathfinding::generate::{range, Generated, Generator, Headway};
+use pathfinding::graph::Graph;
+use pathfinding::input::Input;
+
+/*
+ * Generated timetables: what they look like written out, that a seed pins them down, and that they keep
+ * to the generator's settings.
+ */
+
+#[test]
+fn writes_the_assignment_format() {
+    let generated = Generated { stops: 4, lines: vec![(vec![0, 2, 3], vec![360, 365, 372]), (vec![3, 1], vec![400, 410])] };
+    assert_eq!(generated.to_string(), "2 4\n0 2 3\n360 365 372\n3 1\n400 410\n");
+    assert_eq!(Generated { stops: 3, lines: vec![] }.to_string(), "0 3\n");
+}
+
+#[test]
+fn reads_back_as_the_graph_it_builds() {
+    for seed in 0..20 {
+        let generated = Generator { lines: 5, stops: 20, seed, ..Generator::default() }.generate();
+        let text = generated.to_string();
+        let read: Graph = Graph::read(&mut Input::new(text.as_bytes())).unwrap();
+        assert_eq!(read.to_string(), generated.graph().to_string(), "seed {}", seed);
+    }
+}
+
+#[test]
+fn the_same_seed_gives_the_same_timetable() {
+    let headways = [Headway::Fixed(15), Headway::Uniform(5, 30), Headway::Exponential(12.0)];
+    for headway in headways {
+        let generator = Generator { headway, seed: 99, ..Generator::default() };
+        let first = generator.generate().to_string();
+        assert_eq!(generator.clone().generate().to_string(), first, "{:?}", headway);
+        assert_ne!(Generator { seed: 100, ..generator }.generate().to_string(), first, "{:?}", headway);
+    }
+}
+
+#[test]
+fn keeps_to_its_settings() {
+    for seed in 0..20 {
+        let generator = Generator { lines: 8, stops: 30, length: (3, 6), ride: (2, 5), headway: Headway::Fixed(20), span: (60, 240), seed };
+        let generated = generator.generate();
+        assert_eq!(generated.stops, 30);
+        let mut routes = HashSet::new();
+        let mut last_start = None;
+        for (locations, departures) in &generated.lines {
+            assert!((3..=6).contains(&locations.len()), "seed {}: {:?}", seed, locations);
+            assert_eq!(locations.iter().collect::<HashSet<_>>().len(), locations.len(), "seed {}: {:?}", seed, locations);
+            assert!(locations.iter().all(|&stop| stop < 30));
+            assert!(departures.windows(2).all(|pair| (2..=5).contains(&(pair[1] - pair[0]))), "seed {}: {:?}", seed, departures);
+            assert!((60..=240).contains(&departures[0]), "seed {}: {:?}", seed, departures);
+
+            // Runs of the same line follow each other a headway apart, the first within a headway of the start
+            if routes.insert(locations.clone()) {
+                assert!(departures[0] < 80, "seed {}: {:?}", seed, departures);
+            } else {
+                assert_eq!(departures[0] - last_start.unwrap(), 20, "seed {}", seed);
+            }
+            last_start = Some(departures[0]);
+        }
+        assert_eq!(routes.len(), 8, "seed {}", seed);
+    }
+}
+
+#[test]
+fn reads_headways_and_ranges() {
+    assert_eq!("10".parse(), Ok(Headway::Fixed(10)));
+    assert_eq!("5-20".parse(), Ok(Headway::Uniform(5, 20)));
+    assert_eq!("7-7".parse(), Ok(Headway::Fixed(7)));
+    assert_eq!("exp:12.5".parse(), Ok(Headway::Exponential(12.5)));
+    for bad in ["0", "0-5", "20-5", "exp:0", "exp:-1", "exp:inf", "often", ""] {
+        assert_eq!(bad.parse::<Headway>(), Err(format!("headway '{}' should look like 10, 5-20 or exp:12", bad)));
+    }
+    assert_eq!(range("6:00-22:00"), Some((360, 1320)));
+    assert_eq!(range("4"), Some((4, 4)));
+    assert_eq!(range("10-4"), None);
+}
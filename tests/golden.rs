@@ -0,0 +1,139 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use pathfinding::dot::DotOptions;
+use pathfinding::exclusions::Exclusions;
+use pathfinding::graph::{Graph, Timetable};
+use pathfinding::report::{Answer, Report};
+use pathfinding::solvers::cost::{Fixed, Generalized};
+use pathfinding::solvers::stats::SearchStats;
+use pathfinding::solvers::{self, Solver};
+
+/*
+ * Hand checked instances in tests/fixtures/golden, each NAME.txt with what every solver answers from
+ * Mercator (0) to home (the last stop) in NAME.expected, and the cheapest journey by WEIGHTS. Run with
+ * UPDATE_GOLDEN=1 to write the .expected files from the current answers, then check them by hand before
+ * committing.
+ *
+ *   unreachable_home      nothing goes to 3
+ *   same_minute_transfer  the bus to 2 leaves 1 the minute the bus from 0 gets there
+ *   single_stop_line      a line with only a stop and no legs
+ *   repeated_stop         a line passing 1 twice, getting off and back on is the most waiting but one
+ *   large_times           everything happens just before u32::MAX, home is reached at u32::MAX itself
+ *   ties                  three ways of getting home at 30, two of them waiting 10
+ *
+ * The DOT export of dot/timetable.txt goes the same way, each set of options in its own dot/NAME.dot: two runs
+ * of line 0 going 0 -> 1 -> 2 -> 3, a bus from 0 to 2 and a frequency line from 1 to 3.
+ */
+
+const SOLVERS: [&str; 5] = ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest"];
+
+// Not the defaults, and in hundredths so the rounding shows
+const WEIGHTS: Generalized = Generalized { wait: Fixed(50), ride: Fixed(125), transfer: Fixed(1000) };
+
+fn answers(graph: &Graph) -> String {
+    let home = graph.stop_count() as u32 - 1;
+    let mut answers = String::new();
+    for name in SOLVERS {
+        let (solve, objective): (Solver, _) = solvers::named(name).unwrap();
+        match solve(graph, 0, home, &Exclusions::none(), &mut SearchStats::default()) {
+            Some(journey) => writeln!(answers, "{}: {}\n{}\n", name, objective(&journey), journey).unwrap(),
+            None => writeln!(answers, "{}: No path found to {}.\n", name, home).unwrap(),
+        }
+    }
+    let report = Report::collect(|stats| Answer::cost(graph, 0, home, 0, WEIGHTS, &Exclusions::none(), stats));
+    match &report.answer {
+        Answer::Cost { journey: Some(journey), .. } => writeln!(answers, "cost: {}\n{}\n", report, journey).unwrap(),
+        _ => writeln!(answers, "cost: {}\n", report).unwrap(),
+    }
+    answers
+}
+
+// A line diff over the longest common subsequence, "-" for expected and "+" for actual lines
+fn diff(expected: &str, actual: &str) -> String {
+    let (expected, actual) = (expected.lines().collect::<Vec<_>>(), actual.lines().collect::<Vec<_>>());
+    // common[i][j]: the longest common subsequence of expected[i..] and actual[j..]
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] { common[i + 1][j + 1] + 1 } else { common[i + 1][j].max(common[i][j + 1]) };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            (i, j) = (i + 1, j + 1);
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1]) {
+            writeln!(diff, "- {}", expected[i]).unwrap();
+            i += 1;
+        } else {
+            writeln!(diff, "+ {}", actual[j]).unwrap();
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[test]
+fn solvers_match_golden_files() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden");
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut instances = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .collect::<Vec<_>>();
+    instances.sort();
+    assert!(!instances.is_empty(), "no instances in {}", directory.display());
+
+    let mut failures = String::new();
+    for instance in instances {
+        let actual = answers(&Graph::load(Some(&instance), None).unwrap());
+        let expected_path = instance.with_extension("expected");
+        if update {
+            fs::write(&expected_path, &actual).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if expected != actual {
+            writeln!(failures, "{}:\n{}", instance.display(), diff(&expected, &actual)).unwrap();
+        }
+    }
+    assert!(failures.is_empty(), "answers differ from the golden files\n{}", failures);
+}
+
+#[test]
+fn dot_matches_golden_files() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden/dot");
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut graph = Graph::load(Some(&directory.join("timetable.txt")), None).unwrap();
+    // The assignment format makes every run a line of its own
+    graph.trips[1].line = 0;
+    let (solve, _): (Solver, _) = solvers::named("earliest").unwrap();
+    let journey = solve(&graph, 0, 3, &Exclusions::none(), &mut SearchStats::default()).unwrap();
+
+    let variants = [
+        ("plain", DotOptions::default()),
+        ("colored", DotOptions { color_by_line: true, ..DotOptions::default() }),
+        ("collapsed", DotOptions { collapse: true, ..DotOptions::default() }),
+        ("colored_collapsed", DotOptions { color_by_line: true, collapse: true, ..DotOptions::default() }),
+        ("highlighted", DotOptions { highlight: Some(&journey), collapse: true, ..DotOptions::default() }),
+    ];
+    let mut failures = String::new();
+    for (name, options) in variants {
+        let actual = graph.to_dot(&options);
+        let expected_path = directory.join(name).with_extension("dot");
+        if update {
+            fs::write(&expected_path, &actual).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if expected != actual {
+            writeln!(failures, "{}:\n{}", expected_path.display(), diff(&expected, &actual)).unwrap();
+        }
+    }
+    assert!(failures.is_empty(), "DOT output differs from the golden files\n{}", failures);
+}
@@ -0,0 +1,102 @@
+use pathfinding::exclusions::Exclusions;
+use pathfinding::graph::Graph;
+use pathfinding::gtfs_rt::{self, StopTimeUpdate, TripUpdate};
+use pathfinding::solvers::earliest::dijkstra;
+use pathfinding::solvers::stats::SearchStats;
+
+use common::{fixture, legs, timetable};
+
+mod common;
+
+/*
+ * realtime.txt has trip 0 running 0 -> 1 -> 2 -> 3 at 10, 20, 30, 40, trip 1 going 0 -> 2 from 5 to 35
+ * and trip 2 going 0 -> 3 from 42 to 44.
+ *
+ * 01_delays.pb:         trip "0" 5 minutes late from stop 2 on, trip "1" a minute late
+ * 02_cancellations.pb:  trip "2" cancelled, unknown trip "x", trip "0" 90 seconds early at stop id "3"
+ *
+ * gtfs_rt_loop has trip 0 going round 0 -> 1 -> 2 -> 0 -> 3 at 10, 20, 30, 40, 50, and loop.pb delays it by
+ * stop id: "0" a minute, "2" two minutes, "0" again five minutes and "2" once more, which it doesn't visit again.
+ * gtfs_rt_sequence is a CSV with trip "early" at seq 5, 10, 15 and "late" at seq 1, 2. sequence.pb delays
+ * "early" a minute as a whole and five from seq 10, "late" two minutes from seq 2, and "early" at seq 1.
+ */
+#[test]
+fn decodes_trip_updates() {
+    let updates = gtfs_rt::read(&fixture("gtfs_rt/01_delays.pb")).unwrap();
+
+    assert_eq!(
+        updates,
+        vec![
+            TripUpdate {
+                trip_id: "0".to_string(),
+                cancelled: false,
+                delay: None,
+                stop_time_updates: vec![StopTimeUpdate {
+                    stop_sequence: Some(2),
+                    stop_id: None,
+                    delay: Some(300),
+                    skipped: false,
+                }],
+            },
+            TripUpdate { trip_id: "1".to_string(), cancelled: false, delay: Some(60), stop_time_updates: vec![] },
+        ]
+    );
+}
+
+#[test]
+fn applies_a_directory_of_feeds_in_order() {
+    let mut graph = timetable();
+    assert_eq!(dijkstra(&graph, 0, 3, &Exclusions::none(), &mut SearchStats::default()).map(|journey| journey.arrival()), Some(40));
+
+    let first = gtfs_rt::read(&fixture("gtfs_rt/01_delays.pb")).unwrap();
+    let ingest = graph.apply_trip_updates(&first);
+    assert_eq!(ingest.applied, 2);
+    assert_eq!(dijkstra(&graph, 0, 3, &Exclusions::none(), &mut SearchStats::default()).map(|journey| journey.arrival()), Some(44));
+
+    let mut graph = timetable();
+    let all = gtfs_rt::read(&fixture("gtfs_rt")).unwrap();
+    let ingest = graph.apply_trip_updates(&all);
+    assert_eq!(ingest.applied, 4);
+    assert_eq!(ingest.rejected, vec!["unknown trip 'x'".to_string()]);
+    assert_eq!(dijkstra(&graph, 0, 3, &Exclusions::none(), &mut SearchStats::default()).map(|journey| journey.arrival()), Some(39));
+}
+
+#[test]
+fn rejects_truncated_feeds() {
+    let bytes = std::fs::read(fixture("gtfs_rt/01_delays.pb")).unwrap();
+    assert!(gtfs_rt::decode_feed(&bytes[..bytes.len() - 3]).is_err());
+}
+
+#[test]
+fn a_repeated_stop_id_goes_to_the_visit_after_the_last_update() {
+    let mut graph = Graph::load(Some(&fixture("gtfs_rt_loop/timetable.txt")), None).unwrap();
+    let ingest = graph.apply_trip_updates(&gtfs_rt::read(&fixture("gtfs_rt_loop/loop.pb")).unwrap());
+    assert_eq!(ingest.applied, 3);
+    assert_eq!(ingest.rejected, vec!["trip '0': no stop_id '2' from stop index 4 on".to_string()]);
+    assert_eq!(legs(&graph, 0), [(11, 21, false), (21, 32, false), (32, 45, false), (45, 55, false)]);
+}
+
+#[test]
+fn stop_sequences_are_the_trips_own() {
+    let mut graph = Graph::load(Some(&fixture("gtfs_rt_sequence/timetable.csv")), None).unwrap();
+    assert_eq!(graph.trips[0].sequence, [5, 10, 15]);
+    let ingest = graph.apply_trip_updates(&gtfs_rt::read(&fixture("gtfs_rt_sequence/sequence.pb")).unwrap());
+    assert_eq!(ingest.applied, 3);
+    assert_eq!(ingest.rejected, vec!["trip 'early': no stop_sequence 1".to_string()]);
+    // The trip's delay holds up to seq 10, where the stop's own takes over
+    assert_eq!(legs(&graph, 0), [(11, 25, false), (25, 35, false)]);
+    assert_eq!(legs(&graph, 1), [(25, 42, false)]);
+}
+
+#[test]
+fn keeps_the_trip_delay_alongside_stop_updates() {
+    let mut graph = timetable();
+    let update = TripUpdate {
+        trip_id: "0".to_string(),
+        delay: Some(120),
+        stop_time_updates: vec![StopTimeUpdate { stop_sequence: Some(2), delay: Some(300), ..StopTimeUpdate::default() }],
+        ..TripUpdate::default()
+    };
+    assert_eq!(graph.apply_trip_updates(&[update]).applied, 2);
+    assert_eq!(legs(&graph, 0), [(12, 22, false), (22, 35, false), (35, 45, false)]);
+}
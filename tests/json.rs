@@ -0,0 +1,153 @@
+use pathfinding::exclusions::Exclusions;
+use pathfinding::graph::Graph;
+use pathfinding::input::Input;
+use pathfinding::json::Json;
+use pathfinding::solvers::{self, Solver};
+use pathfinding::solvers::stats::SearchStats;
+
+fn parse(text: &str) -> Result<Json, String> {
+    Json::parse(text)
+}
+
+#[test]
+fn parses_values() {
+    let json = parse(r#" { "a": [1, -2.5, 3e2, 0, -0.0, true, false, null], "b": {}, "c": [] } "#).unwrap();
+    let numbers = json.get("a").and_then(Json::as_array).unwrap();
+    assert_eq!(
+        numbers,
+        [Json::Number(1.0), Json::Number(-2.5), Json::Number(300.0), Json::Number(0.0), Json::Number(-0.0), Json::Bool(true), Json::Bool(false), Json::Null]
+    );
+    assert_eq!(json.get("b"), Some(&Json::Object(vec![])));
+    assert_eq!(json.get("c"), Some(&Json::Array(vec![])));
+    assert_eq!(numbers[2].as_u32(), Some(300));
+    assert_eq!(numbers[1].as_u32(), None);
+    assert_eq!(parse("4294967296").unwrap().as_u32(), None);
+}
+
+#[test]
+fn decodes_escapes() {
+    let text = parse(r#""quote \" backslash \\ slash \/ \b\f\n\r\t é é 🚌""#).unwrap();
+    assert_eq!(text.as_str(), Some("quote \" backslash \\ slash / \u{8}\u{c}\n\r\t é é 🚌"));
+    // And writes them back so they read the same
+    let written = Json::String("\"\\\n\u{1}🚌".to_string()).to_string();
+    assert_eq!(written, r#""\"\\\n\u0001🚌""#);
+    assert_eq!(parse(&written).unwrap(), Json::String("\"\\\n\u{1}🚌".to_string()));
+}
+
+#[test]
+fn rejects_malformed_documents_where_they_go_wrong() {
+    let cases = [
+        ("", "invalid JSON at byte 0: unexpected end of input"),
+        ("[1, 2", "invalid JSON at byte 5: expected ',' or ']'"),
+        ("[1 2]", "invalid JSON at byte 3: expected ',' or ']'"),
+        ("{\"a\" 1}", "invalid JSON at byte 5: expected ':'"),
+        ("{1: 2}", "invalid JSON at byte 1: expected a key"),
+        ("{\"a\": 1,}", "invalid JSON at byte 8: expected a key"),
+        ("nul", "invalid JSON at byte 0: expected 'null'"),
+        ("[1] x", "invalid JSON at byte 4: trailing characters"),
+        ("\"abc", "invalid JSON at byte 4: unterminated string"),
+        ("\"a\nb\"", "invalid JSON at byte 2: unterminated string"),
+        (r#""\x""#, "invalid JSON at byte 3: invalid escape"),
+        (r#""\u12""#, "invalid JSON at byte 3: short unicode escape"),
+        (r#""\u12zz""#, "invalid JSON at byte 3: invalid unicode escape"),
+        (r#""\ud83dA""#, "invalid JSON at byte 7: expected '\\u'"),
+        (r#""\ud83d\u0041""#, "invalid JSON at byte 13: invalid surrogate pair"),
+        (r#""\ude8c""#, "invalid JSON at byte 7: invalid unicode escape"),
+    ];
+    for (text, error) in cases {
+        assert_eq!(parse(text), Err(error.to_string()), "{:?}", text);
+    }
+}
+
+#[test]
+fn keeps_to_the_number_grammar() {
+    for text in ["01", "-", "+1", "1.", ".5", "1e", "1e+", "--1", "0x10", "Infinity", "NaN"] {
+        assert!(parse(text).is_err(), "{:?}", text);
+    }
+    assert_eq!(parse("1e999"), Err("invalid JSON at byte 5: number out of range".to_string()));
+    for (text, value) in [("0", 0.0), ("-0", -0.0), ("10", 10.0), ("1.25", 1.25), ("1E2", 100.0), ("2e-1", 0.2), ("5e+0", 5.0)] {
+        assert_eq!(parse(text), Ok(Json::Number(value)), "{:?}", text);
+    }
+}
+
+#[test]
+fn stops_at_the_depth_limit() {
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    assert!(parse(&nested(256)).is_ok());
+    assert_eq!(parse(&nested(257)), Err("invalid JSON at byte 256: nested too deep".to_string()));
+    // Far past it, without running out of stack
+    assert!(parse(&nested(100_000)).is_err());
+}
+
+const TEXT: &str = "\
+2 4 1
+0 1 3
+10 20 30
+0 2 3
+5 15 50
+1 3
+0 15
+10 0 60
+2
+1111111 20260101 20261231
+1111100 20260101 20261231 -20261225
+0 1 0
+";
+
+const JSON: &str = r#"{
+    "stops": [{}, {}, {}, {}],
+    "lines": [
+        { "stops": [0, 1, 3], "departures": [[10, 20, 30]] },
+        { "stops": [0, 2, 3], "departures": [[5, 15, 50]], "service": 1 },
+        { "stops": [1, 3], "frequency": { "offsets": [0, 15], "headway": 10, "first": 0, "last": 60 } }
+    ],
+    "calendars": ["1111111 20260101 20261231", "1111100 20260101 20261231 -20261225"]
+}"#;
+
+#[test]
+fn builds_the_graph_the_text_format_does() {
+    let text = Graph::read(&mut Input::new(TEXT.as_bytes())).unwrap();
+    let json = Graph::from_json(JSON).unwrap();
+    assert_eq!(json.to_string(), text.to_string());
+    let trips = |graph: &Graph| graph.trips.iter().map(|trip| (trip.line, trip.service, trip.stops.clone())).collect::<Vec<_>>();
+    assert_eq!(trips(&json), trips(&text));
+    let calendars = |graph: &Graph| graph.calendars.iter().map(|calendar| format!("{:?}", calendar)).collect::<Vec<_>>();
+    assert_eq!(calendars(&json), calendars(&text));
+
+    for name in ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest"] {
+        let (solve, _): (Solver, _) = solvers::named(name).unwrap();
+        let answer = |graph: &Graph| solve(graph, 0, 3, &Exclusions::none(), &mut SearchStats::default());
+        assert_eq!(answer(&json), answer(&text), "{}", name);
+    }
+}
+
+#[test]
+fn runs_of_a_line_share_its_number() {
+    let graph = Graph::from_json(r#"{"stops": 2, "lines": [{"stops": [0, 1], "departures": [[1, 2], [3, 4]]}, {"stops": [1, 0], "departures": [[5, 6]]}]}"#).unwrap();
+    assert_eq!(graph.trips.iter().map(|trip| trip.line).collect::<Vec<_>>(), vec![0, 0, 2]);
+}
+
+#[test]
+fn rejects_bad_timetables_with_their_line() {
+    let cases = [
+        (r#"{"lines": []}"#, "timetable has no \"stops\""),
+        (r#"{"stops": -1, "lines": []}"#, "\"stops\" must be a count or an array"),
+        (r#"{"stops": 2}"#, "timetable has no \"lines\" array"),
+        (r#"{"stops": 2, "lines": [{"departures": [[1, 2]]}]}"#, "line 0: \"stops\" must be an array"),
+        (r#"{"stops": 2, "lines": [{"stops": [0, 1], "departures": [[1, 2]]}, {"stops": [0, 5], "departures": [[1, 2]]}]}"#, "line 1: unknown stop 5"),
+        (r#"{"stops": 2, "lines": [{"stops": [0, 1], "departures": [[2, 1]]}]}"#, "line 0: times must not decrease along the line"),
+        (r#"{"stops": 2, "lines": [{"stops": [0, 1], "departures": [[1]]}]}"#, "line 0: 2 stops but 1 times"),
+        (r#"{"stops": 2, "lines": [{"stops": [0, 1], "departures": [[1.5, 2]]}]}"#, "line 0: \"departures\" must hold whole numbers"),
+        (r#"{"stops": 2, "lines": [{"stops": [0, 1], "departures": [[1, 2]], "service": 3}]}"#, "line 0: unknown calendar 3"),
+        (r#"{"stops": 2, "lines": [{"stops": [0, 1], "frequency": {"offsets": [0, 1], "first": 0, "last": 9}}]}"#, "line 0: frequency needs \"headway\""),
+        (r#"{"stops": 2, "lines": [{"stops": [0, 1], "departures": [[1, 2]]}, {"stops": [0, 1]}]}"#, "line 1: no runs, a line needs \"departures\" or a \"frequency\""),
+        (r#"{"stops": 2, "lines": [{"stops": [0, 1], "departures": []}]}"#, "line 0: no runs, a line needs \"departures\" or a \"frequency\""),
+        (r#"{"stops": 2, "lines": [{"stops": [0, 1], "departures": 10}]}"#, "line 0: \"departures\" must be an array"),
+        (r#"{"stops": 2, "lines": [], "calendars": [1]}"#, "a calendar must be a string"),
+        (r#"{"stops": 2, "lines": [], "calendars": ["1111111 2026"]}"#, "invalid date '2026'"),
+        (r#"{"stops": 2, "lines": [}"#, "invalid JSON at byte 23: expected a value"),
+    ];
+    for (text, error) in cases {
+        assert_eq!(Graph::from_json(text).err(), Some(error.to_string()), "{}", text);
+    }
+}
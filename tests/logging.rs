@@ -0,0 +1,87 @@
+use std::path::Path;
+use std::process::Command;
+
+use log::{Level, Record};
+use pathfinding::logger;
+
+/*
+ * What the logger writes for an event, and the events a search logs when pathfind runs with --log.
+ * Setting the logger up can only happen once a process, so that's left to the pathfind runs.
+ */
+
+#[test]
+fn writes_level_target_message_and_fields() {
+    let fields: &[(&str, u32)] = &[("start", 0), ("settled", 12)];
+    let record = Record::builder()
+        .level(Level::Debug)
+        .target("pathfinding::solvers::label")
+        .args(format_args!("search done"))
+        .key_values(&fields)
+        .build();
+    assert_eq!(logger::line(&record), "DEBUG pathfinding::solvers::label: search done start=0 settled=12");
+
+    let record = Record::builder().level(Level::Info).target("pathfind").args(format_args!("ready")).build();
+    assert_eq!(logger::line(&record), "INFO  pathfind: ready");
+}
+
+// What pathfind earliest writes to stdout and stderr with --log `level`
+fn run(level: &str) -> (String, String) {
+    run_on("earliest", "realtime.txt", level)
+}
+
+fn run_on(command: &str, fixture: &str, level: &str) -> (String, String) {
+    let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(fixture);
+    let output = Command::new(env!("CARGO_BIN_EXE_pathfind")).args([command, "--log", level]).arg("--input").arg(input).output().unwrap();
+    (String::from_utf8(output.stdout).unwrap(), String::from_utf8(output.stderr).unwrap())
+}
+
+#[test]
+fn logs_a_line_per_search_at_debug() {
+    let (answer, events) = run("debug");
+    assert_eq!(answer, "40\n");
+    let lines = events.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1, "{}", events);
+    assert!(lines[0].starts_with("DEBUG pathfinding::solvers::label: label setting search done start=0 end=3 "), "{}", events);
+}
+
+#[test]
+fn logs_every_step_at_trace() {
+    let (answer, events) = run("trace");
+    assert_eq!(answer, "40\n");
+    assert!(events.lines().all(|line| line.starts_with("TRACE ") || line.starts_with("DEBUG ")), "{}", events);
+    for step in [
+        ": pop stop=0 time=0",
+        ": relax from=0 to=1 departure=10 arrival=20",
+        ": push stop=1 time=20 cost=20",
+        // Going through 1 gets to 2 sooner than the direct line already queued there
+        ": prune stop=2 time=35 cost=35",
+    ] {
+        assert!(events.contains(step), "{} in {}", step, events);
+    }
+    assert!(events.lines().count() > 3);
+}
+
+// Two runs get to 1 at 20, the second one waiting longer at 0
+#[test]
+fn logs_labels_pruned_at_trace() {
+    for (command, answer, push, prune) in [
+        ("earliest", "30\n", ": push stop=1 time=20 cost=20", ": prune from=0 to=1 arrival=20 cost=20"),
+        ("min-wait", "15\n", ": push stop=1 time=20 cost=10", ": prune from=0 to=1 arrival=20 cost=15"),
+    ] {
+        let (printed, events) = run_on(command, "logging.txt", "trace");
+        assert_eq!(printed, answer, "{}", command);
+        let lines = events.lines().collect::<Vec<_>>();
+        let pushed = lines.iter().position(|line| line.ends_with(push)).unwrap_or_else(|| panic!("{} in {}", push, events));
+        let pruned = lines.iter().position(|line| line.ends_with(prune)).unwrap_or_else(|| panic!("{} in {}", prune, events));
+        assert!(pushed < pruned, "{}", events);
+        assert_eq!(lines.iter().filter(|line| line.contains(": push stop=1 ")).count(), 1, "{}", events);
+    }
+}
+
+#[test]
+fn is_quiet_otherwise() {
+    assert_eq!(run("info"), ("40\n".to_string(), String::new()));
+    let (answer, events) = run("loud");
+    assert_eq!(answer, "");
+    assert!(events.starts_with("unknown log level 'loud'\n"), "{}", events);
+}
@@ -0,0 +1,127 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use pathfinding::exclusions::Exclusions;
+use pathfinding::generate::{Generated, Generator, Headway};
+use pathfinding::graph::Vertex;
+use pathfinding::solvers::stats::SearchStats;
+use pathfinding::solvers::{self, earliest, min_wait, Solver};
+
+use common::{feasible, shrink};
+
+mod common;
+
+/*
+ * What has to hold for any timetable, checked on thousands made by the generator with random settings.
+ * Rides may take no time, so buses can go round in circles within a minute. The queries, the line added
+ * and the stop closed come from the seed, a failing instance is shrunk and printed in the assignment format.
+ *
+ *   adding a bus line never makes earliest arrival later
+ *   closing a stop never makes it earlier
+ *   the least waiting is never more than the earliest journey waits
+ *   every journey a solver returns can be made on the timetable
+ */
+
+const INSTANCES: u64 = 2000;
+const QUERIES: usize = 4;
+
+fn random_instance(seed: u64) -> Generated {
+    let mut rng = StdRng::seed_from_u64(seed);
+    Generator {
+        lines: rng.random_range(1..=6),
+        stops: rng.random_range(2..=12),
+        length: (1, rng.random_range(1..=5)),
+        ride: (0, rng.random_range(0..=10)),
+        headway: Headway::Uniform(1, rng.random_range(1..=30)),
+        span: (0, rng.random_range(0..=90)),
+        seed,
+    }
+    .generate()
+}
+
+// Never getting there is later than anything
+fn arrival(time: Option<u32>) -> u64 {
+    time.map_or(u64::MAX, u64::from)
+}
+
+fn earliest_arrival(instance: &Generated, from: Vertex, to: Vertex, at: u32, exclusions: &Exclusions) -> Option<u32> {
+    earliest::dijkstra_from(&instance.graph(), from, to, at, exclusions, &mut SearchStats::default()).map(|journey| journey.arrival())
+}
+
+fn check(instance: &Generated, seed: u64) -> Option<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let graph = instance.graph();
+    let none = Exclusions::none();
+    let stop = |rng: &mut StdRng| rng.random_range(0..instance.stops);
+
+    for _ in 0..QUERIES {
+        let (from, to, at) = (stop(&mut rng), stop(&mut rng), rng.random_range(0..=60));
+
+        for name in ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest"] {
+            let (solve, _): (Solver, _) = solvers::named(name).unwrap();
+            if let Some(journey) = solve(&graph, from, to, &none, &mut SearchStats::default()) {
+                if let Err(e) = feasible(&graph, &journey, from, to) {
+                    return Some(format!("{} from {} to {} returned an impossible journey, it {}:\n{}", name, from, to, e, journey));
+                }
+            }
+        }
+        let journey = earliest::dijkstra_from(&graph, from, to, at, &none, &mut SearchStats::default());
+        if let Some(Err(e)) = journey.as_ref().map(|journey| feasible(&graph, journey, from, to)) {
+            return Some(format!("earliest from {} to {} at {} returned an impossible journey, it {}", from, to, at, e));
+        }
+        let before = journey.as_ref().map(|journey| journey.arrival());
+
+        // Both set off at 0, so the earliest journey is one of those min-wait picks from
+        let fastest = earliest::dijkstra(&graph, from, to, &none, &mut SearchStats::default());
+        let least = min_wait::dijkstra(&graph, from, to, &none, &mut SearchStats::default());
+        match (&fastest, &least) {
+            (Some(fastest), Some(least)) if least.wait_time() > fastest.wait_time() => {
+                return Some(format!(
+                    "from {} to {} the least waiting is {} but the earliest journey waits {}",
+                    from, to, least.wait_time(), fastest.wait_time()
+                ))
+            }
+            (Some(_), None) => return Some(format!("from {} to {} min-wait finds nothing but earliest does", from, to)),
+            _ => {}
+        }
+
+        let length = rng.random_range(1..=4);
+        let locations = (0..length).map(|_| stop(&mut rng)).collect::<Vec<_>>();
+        let mut time = rng.random_range(0..=90);
+        let departures = (0..length)
+            .map(|_| {
+                time += rng.random_range(0..=10);
+                time
+            })
+            .collect::<Vec<_>>();
+        let mut more = instance.clone();
+        more.lines.push((locations.clone(), departures.clone()));
+        let after = earliest_arrival(&more, from, to, at, &none);
+        if arrival(after) > arrival(before) {
+            return Some(format!(
+                "from {} to {} at {} arrival went from {:?} to {:?} after adding the line {:?} at {:?}",
+                from, to, at, before, after, locations, departures
+            ));
+        }
+
+        let closed = stop(&mut rng);
+        let mut exclusions = Exclusions::none();
+        exclusions.stops.insert(closed);
+        let after = earliest_arrival(instance, from, to, at, &exclusions);
+        if arrival(after) < arrival(before) {
+            return Some(format!("from {} to {} at {} arrival went from {:?} to {:?} after closing stop {}", from, to, at, before, after, closed));
+        }
+    }
+    None
+}
+
+#[test]
+fn routing_invariants_hold() {
+    for seed in 0..INSTANCES {
+        let instance = random_instance(seed);
+        if check(&instance, seed).is_some() {
+            let minimal = shrink(instance, |instance| check(instance, seed));
+            panic!("seed {}: {}\nsmallest failing instance:\n{}", seed, check(&minimal, seed).unwrap(), minimal);
+        }
+    }
+}
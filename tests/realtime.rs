@@ -0,0 +1,150 @@
+use std::process::Command;
+
+use pathfinding::exclusions::Exclusions;
+use pathfinding::graph::Graph;
+use pathfinding::input::Input;
+use pathfinding::realtime::{Status, Update};
+use pathfinding::repl::Session;
+use pathfinding::solvers::earliest::dijkstra;
+use pathfinding::solvers::stats::SearchStats;
+
+use common::{fixture, legs, timetable};
+
+mod common;
+
+/*
+ * Delays and cancellations on realtime.txt (see tests/gtfs_rt.rs), trip 0 running 0 -> 1 -> 2 -> 3 at
+ * 10, 20, 30, 40. updates/late.txt has it 5 minutes late from stop 2 on and cancels trip 1, updates/bad_stop.txt
+ * delays it from a stop it doesn't have in its second update, and updates/not_utf8.txt has bytes in its second
+ * that aren't UTF-8.
+ */
+fn delay(trip: u32, stop_index: u32, seconds: i32) -> Update {
+    Update { trip, stop_index, status: Status::Delayed(seconds) }
+}
+
+fn arrival(graph: &Graph) -> Option<u32> {
+    dijkstra(graph, 0, 3, &Exclusions::none(), &mut SearchStats::default()).map(|journey| journey.arrival())
+}
+
+#[test]
+fn a_delay_pushes_back_the_legs_from_its_stop() {
+    let mut graph = timetable();
+    graph.apply(&delay(0, 2, 5 * 60)).unwrap();
+    // The leg into stop 2 arrives late, the ones before it don't change
+    assert_eq!(legs(&graph, 0), [(10, 20, false), (20, 35, false), (35, 45, false)]);
+    assert_eq!(arrival(&graph), Some(44));
+
+    graph.apply(&delay(0, 0, 60)).unwrap();
+    assert_eq!(legs(&graph, 0), [(11, 21, false), (21, 31, false), (31, 41, false)]);
+}
+
+#[test]
+fn a_delay_is_measured_against_the_plan() {
+    let mut graph = timetable();
+    graph.apply(&delay(0, 1, 10 * 60)).unwrap();
+    // Not 10 + 2 minutes on top of what it was already
+    graph.apply(&delay(0, 1, 2 * 60)).unwrap();
+    assert_eq!(legs(&graph, 0), [(10, 22, false), (22, 32, false), (32, 42, false)]);
+
+    // Back on time, and early rounded towards late
+    graph.apply(&delay(0, 1, 0)).unwrap();
+    assert_eq!(legs(&graph, 0), [(10, 20, false), (20, 30, false), (30, 40, false)]);
+    graph.apply(&delay(0, 1, -90)).unwrap();
+    assert_eq!(legs(&graph, 0), [(10, 19, false), (19, 29, false), (29, 39, false)]);
+}
+
+#[test]
+fn the_same_update_twice_changes_nothing_more() {
+    for update in [delay(0, 1, 150), delay(0, 3, -600), Update { trip: 0, stop_index: 1, status: Status::Cancelled }] {
+        let mut graph = timetable();
+        graph.apply(&update).unwrap();
+        let once = (legs(&graph, 0), graph.to_string());
+        graph.apply(&update).unwrap();
+        assert_eq!((legs(&graph, 0), graph.to_string()), once, "{:?}", update);
+    }
+}
+
+#[test]
+fn a_cancellation_removes_the_legs_from_its_stop() {
+    let mut graph = timetable();
+    graph.apply(&Update { trip: 0, stop_index: 1, status: Status::Cancelled }).unwrap();
+    // Still gets to 1, but leaves no more
+    assert_eq!(legs(&graph, 0), [(10, 20, false), (20, 30, true), (30, 40, true)]);
+    assert_eq!(arrival(&graph), Some(44));
+    let journey = dijkstra(&graph, 0, 1, &Exclusions::none(), &mut SearchStats::default()).unwrap();
+    assert_eq!(journey.arrival(), 20);
+
+    graph.apply(&Update { trip: 2, stop_index: 0, status: Status::Cancelled }).unwrap();
+    assert_eq!(arrival(&graph), None);
+}
+
+#[test]
+fn rejects_updates_the_timetable_has_no_place_for() {
+    let mut graph = timetable();
+    assert_eq!(graph.apply(&delay(3, 0, 60)), Err("unknown trip 3".to_string()));
+    assert_eq!(graph.apply(&delay(0, 4, 60)), Err("trip 0 has no stop 4".to_string()));
+    assert_eq!("0 1".parse::<Update>(), Err("update '0 1' needs a trip, stop index and delay".to_string()));
+    assert_eq!("0 1 late".parse::<Update>(), Err("invalid number 'late' in update '0 1 late'".to_string()));
+    assert_eq!(graph.to_string(), timetable().to_string());
+}
+
+#[test]
+fn applies_a_file_of_updates() {
+    let mut graph = timetable();
+    assert_eq!(graph.apply_file(&fixture("updates/late.txt")), Ok(2));
+    assert_eq!(legs(&graph, 0), [(10, 20, false), (20, 35, false), (35, 45, false)]);
+    assert_eq!(legs(&graph, 1), [(5, 35, true)]);
+
+    // Stops at the bad one, keeping what came before
+    let mut graph = timetable();
+    let path = fixture("updates/bad_stop.txt");
+    assert_eq!(graph.apply_file(&path), Err(format!("{}: update 2: trip 0 has no stop 9", path.display())));
+    assert_eq!(legs(&graph, 0), [(10, 21, false), (21, 31, false), (31, 41, false)]);
+    assert_eq!(legs(&graph, 2), [(42, 44, false)]);
+
+    assert!(graph.apply_file(&fixture("updates/missing.txt")).unwrap_err().starts_with(&fixture("updates/missing.txt").display().to_string()));
+}
+
+#[test]
+fn the_repl_applies_a_file_of_updates() {
+    let mut session = Session::new(timetable());
+    session.execute("whatif 0 3").unwrap();
+    let path = fixture("updates/late.txt");
+    assert_eq!(
+        session.execute(&format!("apply {}", path.display())).unwrap(),
+        "applied 2 updates\nbaseline:   arrives 44, waits 42\nrestricted: arrives 44, waits 42 (+0)"
+    );
+    assert_eq!(session.execute("show line 0").unwrap(), "trip 0 (0)\n  0 -> 1  10 - 20\n  1 -> 2  20 - 35\n  2 -> 3  35 - 45");
+    assert!(session.execute("apply nowhere.txt").unwrap_err().starts_with("nowhere.txt: "));
+}
+
+#[test]
+fn updates_that_arent_text_are_errors() {
+    let path = fixture("updates/not_utf8.txt");
+    let mut graph = timetable();
+    let error = graph.apply_file(&path).unwrap_err();
+    assert_eq!(error, format!("{}: update 2: reading a line: stream did not contain valid UTF-8", path.display()));
+    assert_eq!(legs(&graph, 0), [(10, 21, false), (21, 31, false), (31, 41, false)]);
+
+    let mut session = Session::new(timetable());
+    assert_eq!(session.execute(&format!("apply {}", path.display())), Err(error.clone()));
+    assert!(session.execute("route 0 3").is_ok());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pathfind"))
+        .args(["earliest", "--input"])
+        .arg(fixture("realtime.txt"))
+        .arg("--updates")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), format!("{}\n", error));
+}
+
+#[test]
+fn timetables_that_arent_text_are_errors() {
+    let text = b"1 2\n0 1\n10 20\n1\n\xff111111 20260101 20261231\n0\n";
+    assert_eq!(Graph::<u32>::read(&mut Input::new(&text[..])).err(), Some("reading a calendar: stream did not contain valid UTF-8".to_string()));
+    let text = b"1 2\n0 1\n10 20\n\xff\n";
+    assert_eq!(Graph::<u32>::read(&mut Input::new(&text[..])).err(), Some("reading a line: stream did not contain valid UTF-8".to_string()));
+}
@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use pathfinding::graph::Graph;
+use pathfinding::repl::Session;
+
+/*
+ * Whole REPL sessions on realtime.txt (see tests/gtfs_rt.rs): commands typed in, and everything written
+ * back, prompts included. Errors are answers too and never end the session.
+ */
+
+fn transcript(commands: &[&str]) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/realtime.txt");
+    let mut session = Session::new(Graph::load(Some(&path), None).unwrap());
+    let mut output = vec![];
+    session.run(commands.join("\n").as_bytes(), &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn answers_queries() {
+    let commands = ["route 0 3", "route 0 3 at 0:11", "", "   route 1 3   ", "isochrone 0 25m at 5"];
+    let expected = "\
+> depart 0 at 0
+  0 -> 1  10 - 20  trip 0
+  1 -> 2  20 - 30  trip 0
+  2 -> 3  30 - 40  trip 0
+arrive 3 at 40, waited 10
+> depart 0 at 11
+  0 -> 3  42 - 44  trip 2
+arrive 3 at 44, waited 31
+> > depart 1 at 0
+  1 -> 2  20 - 30  trip 0
+  2 -> 3  30 - 40  trip 0
+arrive 3 at 40, waited 20
+> 3 stops within 25 minutes of 0 at 5
+  0 at 5
+  1 at 20
+  2 at 30
+> \n";
+    assert_eq!(transcript(&commands), expected);
+}
+
+#[test]
+fn keeps_exclusions_between_queries() {
+    let commands = ["closed 1", "route 0 3", "open 1", "cancel line 2", "whatif 0 3", "cancel trip 0", "restore line 2", "restore trip 0", "show line 0"];
+    let expected = "\
+> stop 1 is closed
+> depart 0 at 0
+  0 -> 3  42 - 44  trip 2
+arrive 3 at 44, waited 42
+> stop 1 is open
+> line 2 is cancelled
+> baseline:   arrives 40, waits 10
+restricted: arrives 40, waits 10 (+0)
+> trip 0 is cancelled
+baseline:   arrives 40, waits 10
+restricted: no journey
+- 0 -> 1  10 - 20  trip 0
+- 1 -> 2  20 - 30  trip 0
+- 2 -> 3  30 - 40  trip 0
+> line 2 runs
+baseline:   arrives 40, waits 10
+restricted: arrives 44, waits 42 (+4)
+- 0 -> 1  10 - 20  trip 0
+- 1 -> 2  20 - 30  trip 0
+- 2 -> 3  30 - 40  trip 0
++ 0 -> 3  42 - 44  trip 2
+> trip 0 runs
+baseline:   arrives 40, waits 10
+restricted: arrives 40, waits 10 (+0)
+> trip 0 (0)
+  0 -> 1  10 - 20
+  1 -> 2  20 - 30
+  2 -> 3  30 - 40
+> \n";
+    assert_eq!(transcript(&commands), expected);
+}
+
+#[test]
+fn errors_dont_end_the_session() {
+    let commands = [
+        "stats",
+        "route 0",
+        "route 0 9",
+        "route 0 3 at 25:99",
+        "route 0 3 after 5",
+        "isochrone 0 forever",
+        "closed x",
+        "cancel bus 1",
+        "cancel line 9",
+        "restore trip 3",
+        "show line 7",
+        "whatif 0 3 fastest",
+        "frobnicate",
+        "route 0 2",
+        "stats",
+        "quit",
+        "route 0 3",
+    ];
+    let expected = "\
+> error: no query yet
+> error: don't know 'route 0', try help
+> error: there is no stop '9'
+> error: '25:99' is not minutes or H:MM
+> error: expected 'at TIME' but got 'after 5'
+> error: 'forever' is not a duration like 30m
+> error: there is no stop 'x'
+> error: don't know 'cancel bus 1', try help
+> error: there is no line '9'
+> error: there is no trip '3'
+> error: there is no line 7
+> error: there is no solver 'fastest'
+> error: don't know 'frobnicate', try help
+> depart 0 at 0
+  0 -> 1  10 - 20  trip 0
+  1 -> 2  20 - 30  trip 0
+arrive 2 at 30, waited 10
+> settled 2, relaxed 4, heap max 3, dominated 0, cache 0 hits 0 misses, ";
+    // Then how long the search took, and nothing after quit
+    let transcript = transcript(&commands);
+    let (answered, elapsed) = transcript.split_at(expected.len().min(transcript.len()));
+    assert_eq!(answered, expected);
+    assert!(elapsed.ends_with("s\n> ") && elapsed.lines().count() == 2, "{:?}", elapsed);
+}
+
+#[test]
+fn help_lists_every_command() {
+    let help = transcript(&["help"]);
+    for command in ["route", "isochrone", "closed", "open", "cancel", "restore", "apply", "whatif", "show line", "stats", "help", "quit"] {
+        assert!(help.lines().any(|line| line.trim_start_matches("> ").starts_with(command)), "{}", command);
+    }
+}
@@ -0,0 +1,156 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use pathfinding::graph::Graph;
+use pathfinding::json::Json;
+use pathfinding::server::serve;
+
+// Serves realtime.txt (see tests/gtfs_rt.rs) on a free port
+fn start() -> SocketAddr {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/realtime.txt");
+    let graph = Graph::load(Some(&path), None).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || serve(listener, Arc::new(graph)));
+    address
+}
+
+// The status line and the parsed body
+fn get(address: SocketAddr, target: &str) -> (String, Json) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    (head.lines().next().unwrap().to_string(), Json::parse(body).unwrap())
+}
+
+#[test]
+fn answers_health_and_routes() {
+    let address = start();
+
+    let (status, health) = get(address, "/health");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(health.get("stops"), Some(&Json::Number(4.0)));
+
+    let (status, route) = get(address, "/route?from=0&to=3");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(route.get("answer"), Some(&Json::Number(40.0)));
+    assert_eq!(route.get("journey").and_then(|journey| journey.get("legs")).and_then(Json::as_array).map(<[Json]>::len), Some(3));
+
+    let (_, route) = get(address, "/route?from=0&to=3&solver=max-wait");
+    assert_eq!(route.get("answer"), Some(&Json::Number(42.0)));
+
+    // Trip 0 leaves at 10, so setting off at 11 only trip 2 is left
+    let (_, route) = get(address, "/route?from=0&to=3&at=0%3A11");
+    assert_eq!(route.get("answer"), Some(&Json::Number(44.0)));
+
+    // With waiting cheap it pays to wait for the short ride of trip 2
+    let (_, cost) = get(address, "/cost?from=0&to=3&wait=0.5&ride=1.25&transfer=5");
+    assert_eq!(cost.get("answer"), Some(&Json::Number(23.5)));
+    assert_eq!(cost.get("breakdown").and_then(|breakdown| breakdown.get("transfers")), Some(&Json::Number(0.0)));
+    let (_, cost) = get(address, "/cost?from=0&to=3");
+    assert_eq!(cost.get("breakdown").and_then(|breakdown| breakdown.get("wait_cost")), Some(&Json::Number(20.0)));
+
+    let (_, profile) = get(address, "/profile?from=0&to=3");
+    assert_eq!(profile.get("profile").and_then(Json::as_array).map(<[Json]>::len), Some(2));
+
+    let (_, isochrone) = get(address, "/isochrone?from=0&within=30m");
+    let reached = isochrone.get("reached").and_then(Json::as_array).unwrap();
+    assert_eq!(reached.iter().filter_map(|stop| stop.get("stop")?.as_u32()).collect::<Vec<_>>(), vec![0, 1, 2]);
+}
+
+#[test]
+fn rejects_bad_requests() {
+    let address = start();
+
+    let (status, body) = get(address, "/route?from=0&to=9");
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+    assert_eq!(body.get("error").and_then(Json::as_str), Some("there is no stop '9'"));
+
+    assert_eq!(get(address, "/route?from=0&to=3&solver=magic").0, "HTTP/1.1 400 Bad Request");
+    assert_eq!(get(address, "/isochrone?from=0").0, "HTTP/1.1 400 Bad Request");
+    let (status, body) = get(address, "/cost?from=0&to=3&wait=1.125");
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+    assert_eq!(body.get("error").and_then(Json::as_str), Some("wait: '1.125' is not a number with at most two decimals"));
+    let (status, body) = get(address, "/cost?from=0&to=3&walk=1.5");
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+    assert_eq!(body.get("error").and_then(Json::as_str), Some("there are no footpaths in the timetable, so there is no walking to weigh"));
+    for (target, solver) in [("/route?from=0&to=3&at=10&solver=min-wait", "min-wait"), ("/profile?from=0&to=3&at=10", "profile")] {
+        let (status, body) = get(address, target);
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        let error = format!("{} doesn't take an at, only earliest, cost and isochrone do", solver);
+        assert_eq!(body.get("error").and_then(Json::as_str), Some(error.as_str()));
+    }
+    assert_eq!(get(address, "/route?from=0&to=3&solver=min-wait").0, "HTTP/1.1 200 OK");
+    assert_eq!(get(address, "/timetable").0, "HTTP/1.1 404 Not Found");
+}
+
+#[test]
+fn serves_concurrent_requests() {
+    let address = start();
+
+    let clients = (0..16)
+        .map(|i| {
+            thread::spawn(move || {
+                let target = if i % 2 == 0 { "/route?from=0&to=3" } else { "/route?from=0&to=2" };
+                get(address, target).1.get("answer").and_then(Json::as_u32)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (i, client) in clients.into_iter().enumerate() {
+        assert_eq!(client.join().unwrap(), Some(if i % 2 == 0 { 40 } else { 30 }));
+    }
+}
+
+// The status line of whatever comes back for raw request bytes
+fn send(address: SocketAddr, request: &[u8]) -> String {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(request).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.lines().next().unwrap_or_default().to_string()
+}
+
+#[test]
+fn limits_what_a_request_may_send() {
+    let address = start();
+
+    let long = format!("GET /route?from=0&to=3&pad={} HTTP/1.1\r\n\r\n", "x".repeat(8 * 1024));
+    assert_eq!(send(address, long.as_bytes()), "HTTP/1.1 414 URI Too Long");
+    let headers = format!("GET /health HTTP/1.1\r\n{}\r\n", "X-Padding: yes\r\n".repeat(5000));
+    assert_eq!(send(address, headers.as_bytes()), "HTTP/1.1 431 Request Header Fields Too Large");
+    // Just under both limits is fine
+    let headers = format!("GET /health HTTP/1.1\r\n{}\r\n", "X-Padding: yes\r\n".repeat(4000));
+    assert_eq!(send(address, headers.as_bytes()), "HTTP/1.1 200 OK");
+    assert_eq!(send(address, b"BREW /coffee\r\n\r\n"), "HTTP/1.1 400 Bad Request");
+}
+
+#[test]
+fn hangs_up_on_clients_that_say_nothing() {
+    let address = start();
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(30))).unwrap();
+    let started = Instant::now();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert_eq!(response, "");
+    assert!(started.elapsed() < Duration::from_secs(20), "{:?}", started.elapsed());
+}
+
+#[test]
+fn decodes_only_whole_escapes() {
+    let address = start();
+    let (_, route) = get(address, "/route?from=%30&to=%33");
+    assert_eq!(route.get("answer"), Some(&Json::Number(40.0)));
+    for (target, stop) in [("/route?from=%+0&to=3", "% 0"), ("/route?from=%3&to=3", "%3"), ("/route?from=%-1&to=3", "%-1"), ("/route?from=0%&to=3", "0%")] {
+        let (status, body) = get(address, target);
+        assert_eq!(status, "HTTP/1.1 400 Bad Request", "{}", target);
+        assert_eq!(body.get("error").and_then(Json::as_str), Some(format!("there is no stop '{}'", stop).as_str()), "{}", target);
+    }
+}
@@ -0,0 +1,104 @@
+use pathfinding::dot::DotOptions;
+use pathfinding::exclusions::Exclusions;
+use pathfinding::generate::Generator;
+use pathfinding::graph::{Graph, Timetable, EVERY_DAY};
+use pathfinding::input::Input;
+use pathfinding::realtime::{Status, Update};
+use pathfinding::repl::Session;
+use pathfinding::solvers::stats::SearchStats;
+use pathfinding::solvers::{self, earliest};
+use pathfinding::time::TimeLike;
+
+/*
+ * Times other than u32 minutes, and what happens near the end of time. The solvers answer the same in any
+ * time type the timetable fits in, and timetables that don't fit are errors instead of wrapping round.
+ */
+
+fn read<T: TimeLike>(text: &str) -> Result<Graph<T>, String> {
+    Graph::read(&mut Input::new(text.as_bytes()))
+}
+
+// Every solver's answer on the graph as built and frozen, the same for both
+fn answers<G: Timetable>(graph: &G, frozen: &impl Timetable<Time = G::Time>, to: u32) -> Vec<Option<G::Time>> {
+    let mut answers = vec![];
+    for name in ["earliest", "min-wait", "min-transfers", "max-wait", "dag-longest"] {
+        let (solve, objective) = solvers::named::<G>(name).unwrap();
+        let answer = solve(graph, 0, to, &Exclusions::none(), &mut SearchStats::default()).map(|journey| objective(&journey));
+        let (solve, objective) = solvers::named(name).unwrap();
+        let on_frozen = solve(frozen, 0, to, &Exclusions::none(), &mut SearchStats::default()).map(|journey| objective(&journey));
+        assert_eq!(answer, on_frozen, "{}", name);
+        answers.push(answer);
+    }
+    answers
+}
+
+#[test]
+fn seconds_past_u32() {
+    // A day of seconds from 2106 on, when u32 seconds since 1970 run out
+    let text = "3 3\n0 1\n5000000000 5000000600\n1 2\n5000000900 5000001500\n0 2\n5000000100 5000003600\n";
+    assert!(read::<u32>(text).err().unwrap().contains("bad departure"));
+
+    let graph = read::<u64>(text).unwrap();
+    let answers = answers(&graph, &graph.freeze(), 2);
+    assert_eq!(answers, [Some(5000001500), Some(5000000100), Some(0), Some(5000000300), Some(5000000300)]);
+
+    let journey = earliest::dijkstra_from(&graph, 0, 2, 5000000001, &Exclusions::none(), &mut SearchStats::default()).unwrap();
+    assert_eq!((journey.arrival(), journey.wait_time()), (5000003600, 99));
+}
+
+#[test]
+fn any_time_type_gives_the_same_answers() {
+    for seed in 0..50 {
+        let generated = Generator { lines: 6, stops: 12, span: (0, 120), seed, ..Generator::default() }.generate();
+        let minutes = generated.graph();
+        let mut wide = Graph::<u64>::new(generated.stops as usize);
+        for (locations, departures) in &generated.lines {
+            wide.add_trip(locations, &departures.iter().map(|&time| time as u64).collect::<Vec<_>>(), EVERY_DAY);
+        }
+        let home = generated.stops - 1;
+        let expected = answers(&minutes, &minutes.freeze(), home).into_iter().map(|answer| answer.map(u64::from));
+        assert_eq!(answers(&wide, &wide.freeze(), home), expected.collect::<Vec<_>>(), "seed {}", seed);
+    }
+}
+
+#[test]
+fn frequency_lines_stop_at_the_end_of_time() {
+    // The last run leaves 0 at 65530 in u16 minutes, the one after would wrap round to 4
+    let text = "0 2 1\n0 1\n0 2\n10 65500 65530\n";
+    let graph = read::<u16>(text).unwrap();
+    let runs = graph.every_departure(0, 65525, &Exclusions::none()).map(|edge| edge.departure_time).collect::<Vec<_>>();
+    assert_eq!(runs, [65530]);
+    let journey = earliest::dijkstra_from(&graph, 0, 1, 65525, &Exclusions::none(), &mut SearchStats::default()).unwrap();
+    assert_eq!(journey.arrival(), 65532);
+
+    let past = "0 2 1\n0 1\n0 10\n10 65500 65530\n";
+    assert!(read::<u16>(past).err().unwrap().contains("past the end of time"));
+}
+
+#[test]
+fn delays_past_the_end_of_time_are_errors() {
+    let mut graph = read::<u32>(&format!("1 2\n0 1\n{} {}\n", u32::MAX - 100, u32::MAX - 10)).unwrap();
+    let update = Update { trip: 0, stop_index: 1, status: Status::Delayed(60 * 60) };
+    assert!(graph.apply(&update).unwrap_err().contains("past the end of time"));
+    let edge = graph.adjacency[0][0];
+    assert_eq!((edge.departure_time, edge.arrival()), (u32::MAX - 100, u32::MAX - 10));
+
+    graph.apply(&Update { trip: 0, stop_index: 1, status: Status::Delayed(10 * 60) }).unwrap();
+    assert_eq!(graph.adjacency[0][0].arrival(), u32::MAX);
+}
+
+#[test]
+fn showing_the_end_of_time() {
+    // Built by hand, so nothing stops a leg or a frequency line from going past the last minute there is
+    let mut graph = Graph::<u32>::new(3);
+    graph.add_trip(&[0, 1], &[u32::MAX - 10, u32::MAX], EVERY_DAY);
+    graph.adjacency[0][0].weight = 20;
+    graph.add_frequency(&[1, 2], &[10, 15], 10, (u32::MAX - 5, u32::MAX - 5), EVERY_DAY);
+    let mut session = Session::new(graph);
+    assert_eq!(session.execute("show line 0").unwrap(), format!("trip 0 (0)\n  0 -> 1  {} - {}", u32::MAX - 10, u32::MAX));
+    assert_eq!(session.execute("show line 1").unwrap(), format!("trip 1 (1)\n  1 -> 2  every 10 from {} to {}", u32::MAX, u32::MAX));
+
+    let dot = session.graph.to_dot(&DotOptions { color_by_line: false, highlight: None, collapse: false });
+    assert!(dot.contains(&format!("0 -> 1 [label=\"{} - {}\"];", u32::MAX - 10, u32::MAX)), "{}", dot);
+    assert!(dot.contains(&format!("1 -> 2 [label=\"every 10 from {} to {}\"];", u32::MAX, u32::MAX)), "{}", dot);
+}